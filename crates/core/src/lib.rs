@@ -8,6 +8,82 @@ pub struct BundleComponent {
     pub ptr: *const u8,
 }
 
+/// A component to be fetched for each entity during batched query iteration.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct FetchComponent {
+    pub component_id: usize,
+    pub is_mut: bool,
+}
+
+/// A term of a query filter, passed to the host when a query is transmuted or joined.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct QueryTerm {
+    pub component_id: usize,
+    pub kind: QueryTermKind,
+}
+
+/// How a [`QueryTerm`] accesses its component.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QueryTermKind {
+    Ref,
+    Mut,
+    With,
+    Without,
+}
+
+/// The context a component hook was invoked with.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct ComponentHookContext {
+    pub entity: u64,
+    pub component_id: usize,
+    /// The caller's file, or null if the host doesn't track caller locations.
+    pub caller_file_ptr: *const u8,
+    pub caller_file_len: usize,
+    pub caller_line: u32,
+    pub caller_column: u32,
+    /// 0 for `Run`, 1 for `RunIfNotLinked` and 2 for `Skip`.
+    pub relationship_hook_mode: u8,
+}
+
+/// Component lifecycle event a guest hook is attached to.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComponentHookKind {
+    Add,
+    Insert,
+    Replace,
+    Remove,
+    Despawn,
+}
+
+/// The outcome of a guest resource scope.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResourceScopeResult {
+    /// The scope ran and the resource was moved back into the world.
+    Ran,
+    /// The resource doesn't exist.
+    NotFound,
+    /// The resource has drop glue, so it can't be moved out of the world by copying its bytes.
+    NeedsDrop,
+}
+
+/// The outcome of registering a guest component.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegisterComponentResult {
+    /// The component was registered.
+    Registered,
+    /// The size and alignment of the component don't form a valid layout.
+    InvalidLayout,
+    /// Every host drop function is used by a component whose values are still alive.
+    NoDropSlots,
+}
+
 /// Opaque type for World pointers.
 pub enum world {}
 
@@ -20,9 +96,15 @@ pub enum query_state {}
 /// Opaque type for Query pointers.
 pub enum query {}
 
+/// Opaque type for QueryLens pointers.
+pub enum query_lens {}
+
 /// Opaque type for QueryIter pointers.
 pub enum query_iter {}
 
+/// Opaque type for QueryCombinationIter pointers.
+pub enum query_combination_iter {}
+
 /// Opaque type for EntityWorldMut pointers.
 pub enum entity_world_mut {}
 
@@ -56,12 +138,48 @@ pub enum commands {}
 /// Opaque type for DeferredWorld pointers.
 pub enum deferred_world {}
 
+/// Opaque type for ParamSet pointers.
+pub enum param_set {}
+
+/// Opaque type for EntityMapper pointers.
+pub enum entity_mapper {}
+
 pub type RunSystemFn =
     unsafe extern "C" fn(*mut (), *const *mut dyn_system_param, usize, *const u8, *mut u8);
 
 pub type RunObserverFn =
     unsafe extern "C" fn(*mut (), *const *mut dyn_system_param, usize, *mut trigger);
 
+/// Drops a closure that was handed to the host with a [`RunSystemFn`] or [`RunObserverFn`].
+pub type DropClosureFn = unsafe extern "C" fn(*mut ());
+
+/// Runs a guest closure over `len` entities and pointers to their fetched components.
+pub type RunChunkFn = unsafe extern "C" fn(*mut (), *const u64, *const *mut u8, usize);
+
+pub type CompareFn = unsafe extern "C" fn(*mut (), *const u8, *const u8) -> i8;
+
 pub type RunCommandFn = unsafe extern "C" fn(*mut (), *mut world);
 
-pub type ComponentHookFn = unsafe extern "C" fn(*mut deferred_world, u64, usize);
+pub type RunResourceScopeFn = unsafe extern "C" fn(*mut (), *mut world, *mut u8);
+
+pub type ComponentHookFn = unsafe extern "C" fn(*mut deferred_world, *const ComponentHookContext);
+
+pub type ComponentObserverFn =
+    unsafe extern "C" fn(*const (), *mut deferred_world, *const ComponentHookContext);
+
+pub type RequiredConstructorFn = unsafe extern "C" fn(*const (), *mut u8);
+
+pub type ComponentCloneFn = unsafe extern "C" fn(
+    *const (),
+    *const u8,
+    *mut u8,
+    u64,
+    u64,
+    usize,
+    *mut entity_mapper,
+) -> bool;
+
+pub type MapEntitiesFn = unsafe extern "C" fn(*mut u8, *mut entity_mapper);
+
+/// Drops a component in place.
+pub type ComponentDropFn = unsafe extern "C" fn(*mut u8);
@@ -1,8 +1,15 @@
-use crate::world::DeferredWorld;
+use crate::world::{DeferredWorld, World, hook_context};
 use bevy_ecs::component::ComponentId;
-use bevy_ecs::entity::{Entity, EntityMapper};
+use bevy_ecs::entity::Entity;
+use bevy_ecs::ptr::Ptr;
+use bevy_mod_ffi_core::{ComponentHookContext, ComponentHookKind, deferred_world, entity_mapper};
 use bevy_reflect::TypePath;
-use bytemuck::Pod;
+use std::{fmt, marker::PhantomData, mem, ptr::NonNull};
+
+pub use bevy_ecs::{
+    entity::{EntityMapper, MapEntities},
+    relationship::RelationshipHookMode,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StorageType {
@@ -10,19 +17,54 @@ pub enum StorageType {
     SparseSet,
 }
 
-pub trait ComponentMutability: 'static {}
+pub trait ComponentMutability: 'static {
+    const MUTABLE: bool;
+}
 
 pub struct Mutable;
-impl ComponentMutability for Mutable {}
+impl ComponentMutability for Mutable {
+    const MUTABLE: bool = true;
+}
 
 pub struct Immutable;
-impl ComponentMutability for Immutable {}
+impl ComponentMutability for Immutable {
+    const MUTABLE: bool = false;
+}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy)]
 pub struct HookContext {
     pub entity: Entity,
     pub component_id: ComponentId,
-    pub caller: Option<&'static std::panic::Location<'static>>,
+    /// The location that caused the hook, if the host tracks caller locations.
+    pub caller: Option<CallerLocation>,
+    pub relationship_hook_mode: RelationshipHookMode,
+}
+
+// `RelationshipHookMode` doesn't implement `PartialEq`, so its variants are compared instead
+impl PartialEq for HookContext {
+    fn eq(&self, other: &Self) -> bool {
+        self.entity == other.entity
+            && self.component_id == other.component_id
+            && self.caller == other.caller
+            && mem::discriminant(&self.relationship_hook_mode)
+                == mem::discriminant(&other.relationship_hook_mode)
+    }
+}
+
+impl Eq for HookContext {}
+
+/// A source location in the host, reported for component hooks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallerLocation {
+    pub file: &'static str,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl fmt::Display for CallerLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
 }
 
 #[derive(Clone)]
@@ -32,14 +74,283 @@ pub enum ComponentCloneBehavior {
     Custom(fn(&SourceComponent, &mut ComponentCloneCtx)),
 }
 
-pub struct SourceComponent;
-pub struct ComponentCloneCtx;
+impl ComponentCloneBehavior {
+    /// Clones the component with its [`Clone`] implementation.
+    pub fn clone<C: SharedComponent + Clone>() -> Self {
+        Self::Custom(component_clone_via_clone::<C>)
+    }
+}
+
+fn component_clone_via_clone<C: SharedComponent + Clone>(
+    source: &SourceComponent,
+    ctx: &mut ComponentCloneCtx,
+) {
+    if let Some(component) = source.read::<C>() {
+        ctx.write_target_component(component.clone());
+    }
+}
+
+/// Read access to the component being cloned.
+pub struct SourceComponent<'a> {
+    ptr: Ptr<'a>,
+    type_path: &'static str,
+}
+
+impl<'a> SourceComponent<'a> {
+    /// Returns the source component, or `None` if it is not a `C`.
+    pub fn read<C: SharedComponent>(&self) -> Option<&C> {
+        (C::type_path() == self.type_path).then(|| unsafe { self.ptr.deref::<C>() })
+    }
+
+    pub fn ptr(&self) -> Ptr<'a> {
+        self.ptr
+    }
+}
+
+/// Context for a custom [`ComponentCloneBehavior`].
+pub struct ComponentCloneCtx<'a> {
+    source: Entity,
+    target: Entity,
+    component_id: ComponentId,
+    type_path: &'static str,
+    target_ptr: *mut u8,
+    target_component_written: bool,
+    mapper: HostEntityMapper,
+    _marker: PhantomData<&'a mut ()>,
+}
+
+impl ComponentCloneCtx<'_> {
+    pub fn source(&self) -> Entity {
+        self.source
+    }
+
+    pub fn target(&self) -> Entity {
+        self.target
+    }
+
+    pub fn component_id(&self) -> ComponentId {
+        self.component_id
+    }
+
+    pub fn target_component_written(&self) -> bool {
+        self.target_component_written
+    }
+
+    pub fn entity_mapper(&mut self) -> &mut dyn EntityMapper {
+        &mut self.mapper
+    }
+
+    /// Writes the cloned component to the target entity, mapping its entities first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component was already written or `C` is not the component being cloned.
+    pub fn write_target_component<C: SharedComponent>(&mut self, mut component: C) {
+        assert!(
+            !self.target_component_written,
+            "Trying to write component '{}' multiple times",
+            C::type_path()
+        );
+        assert_eq!(
+            C::type_path(),
+            self.type_path,
+            "Component does not match the source component"
+        );
+
+        C::map_entities(&mut component, &mut self.mapper);
+        unsafe { self.target_ptr.cast::<C>().write(component) };
+        self.target_component_written = true;
+    }
+}
+
+/// An [`EntityMapper`] owned by the host.
+struct HostEntityMapper {
+    ptr: *mut entity_mapper,
+}
+
+impl EntityMapper for HostEntityMapper {
+    fn get_mapped(&mut self, source: Entity) -> Entity {
+        let bits = unsafe {
+            bevy_mod_ffi_guest_sys::component::bevy_entity_mapper_get_mapped(
+                self.ptr,
+                source.to_bits(),
+            )
+        };
+        Entity::from_bits(bits)
+    }
+
+    fn set_mapped(&mut self, source: Entity, target: Entity) {
+        unsafe {
+            bevy_mod_ffi_guest_sys::component::bevy_entity_mapper_set_mapped(
+                self.ptr,
+                source.to_bits(),
+                target.to_bits(),
+            )
+        }
+    }
+}
+
+pub(crate) unsafe extern "C" fn clone_wrapper<C: SharedComponent>(
+    clone_fn: *const (),
+    source_ptr: *const u8,
+    target_ptr: *mut u8,
+    source_bits: u64,
+    target_bits: u64,
+    component_id: usize,
+    mapper: *mut entity_mapper,
+) -> bool {
+    let clone_fn = unsafe {
+        mem::transmute::<*const (), fn(&SourceComponent, &mut ComponentCloneCtx)>(clone_fn)
+    };
+
+    let source = SourceComponent {
+        ptr: unsafe { Ptr::new(NonNull::new_unchecked(source_ptr as *mut u8)) },
+        type_path: C::type_path(),
+    };
+    let mut ctx = ComponentCloneCtx {
+        source: Entity::from_bits(source_bits),
+        target: Entity::from_bits(target_bits),
+        component_id: ComponentId::new(component_id),
+        type_path: C::type_path(),
+        target_ptr,
+        target_component_written: false,
+        mapper: HostEntityMapper { ptr: mapper },
+        _marker: PhantomData,
+    };
+    clone_fn(&source, &mut ctx);
+
+    ctx.target_component_written
+}
+
+pub(crate) unsafe extern "C" fn drop_wrapper<C: SharedComponent>(ptr: *mut u8) {
+    unsafe { ptr.cast::<C>().drop_in_place() };
+}
+
+pub(crate) unsafe extern "C" fn map_entities_wrapper<C: SharedComponent>(
+    ptr: *mut u8,
+    mapper: *mut entity_mapper,
+) {
+    let component = unsafe { &mut *ptr.cast::<C>() };
+    C::map_entities(component, &mut HostEntityMapper { ptr: mapper });
+}
+
+/// Registers the components required by a [`SharedComponent`].
+pub struct RequiredComponentsRegistrator<'a> {
+    world: &'a mut World,
+    requiree: ComponentId,
+}
+
+impl<'a> RequiredComponentsRegistrator<'a> {
+    pub(crate) fn new(world: &'a mut World, requiree: ComponentId) -> Self {
+        Self { world, requiree }
+    }
+
+    /// Registers `R` as required, inserting `constructor()` when it is missing from a spawned entity.
+    pub fn register_required<R: SharedComponent>(&mut self, constructor: fn() -> R) {
+        let required = self
+            .world
+            .get_component_id::<R>()
+            .unwrap_or_else(|| self.world.register_component::<R>());
+
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::world::bevy_world_register_required_component(
+                self.world.ptr,
+                self.requiree.index(),
+                required.index(),
+                construct_required::<R>,
+                constructor as *const (),
+            )
+        };
+        assert!(
+            success,
+            "Failed to register required component: {}",
+            R::type_path()
+        );
+    }
+}
+
+/// Hooks attached to a component, which may be owned by the host.
+///
+/// These run as observers after any hooks set by the component's owner and are removed when the
+/// guest library is unloaded.
+pub struct ComponentHooks<'a> {
+    world: &'a mut World,
+    component_id: ComponentId,
+}
+
+impl<'a> ComponentHooks<'a> {
+    pub(crate) fn new(world: &'a mut World, component_id: ComponentId) -> Self {
+        Self {
+            world,
+            component_id,
+        }
+    }
+
+    pub fn on_add(&mut self, hook: for<'w> fn(DeferredWorld<'w>, HookContext)) -> &mut Self {
+        self.add_hook(ComponentHookKind::Add, hook)
+    }
+
+    pub fn on_insert(&mut self, hook: for<'w> fn(DeferredWorld<'w>, HookContext)) -> &mut Self {
+        self.add_hook(ComponentHookKind::Insert, hook)
+    }
+
+    pub fn on_replace(&mut self, hook: for<'w> fn(DeferredWorld<'w>, HookContext)) -> &mut Self {
+        self.add_hook(ComponentHookKind::Replace, hook)
+    }
+
+    pub fn on_remove(&mut self, hook: for<'w> fn(DeferredWorld<'w>, HookContext)) -> &mut Self {
+        self.add_hook(ComponentHookKind::Remove, hook)
+    }
+
+    pub fn on_despawn(&mut self, hook: for<'w> fn(DeferredWorld<'w>, HookContext)) -> &mut Self {
+        self.add_hook(ComponentHookKind::Despawn, hook)
+    }
+
+    fn add_hook(
+        &mut self,
+        kind: ComponentHookKind,
+        hook: for<'w> fn(DeferredWorld<'w>, HookContext),
+    ) -> &mut Self {
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::world::bevy_world_add_component_hook(
+                self.world.ptr,
+                self.component_id.index(),
+                kind,
+                component_hook_wrapper,
+                hook as *const (),
+            )
+        };
+        assert!(success, "Failed to add component hook: {kind:?}");
+        self
+    }
+}
+
+unsafe extern "C" fn component_hook_wrapper(
+    hook: *const (),
+    deferred_ptr: *mut deferred_world,
+    context: *const ComponentHookContext,
+) {
+    let hook =
+        unsafe { mem::transmute::<*const (), for<'w> fn(DeferredWorld<'w>, HookContext)>(hook) };
+    let deferred = unsafe { DeferredWorld::from_ptr(deferred_ptr) };
+    let context = unsafe { hook_context(&*context) };
+    hook(deferred, context);
+}
 
-pub struct RequiredComponentsRegistrator<'a, 'b> {
-    _marker: std::marker::PhantomData<(&'a (), &'b ())>,
+unsafe extern "C" fn construct_required<R: SharedComponent>(
+    constructor: *const (),
+    out_ptr: *mut u8,
+) {
+    let constructor = unsafe { mem::transmute::<*const (), fn() -> R>(constructor) };
+    unsafe { out_ptr.cast::<R>().write(constructor()) };
 }
 
-pub trait SharedComponent: Pod + TypePath + Sized + Send + Sync + 'static {
+/// A component that can be shared with the host.
+///
+/// Components that need dropping are dropped by the guest, so they may own memory from the guest
+/// allocator. These are not cloned unless they set a [`ComponentCloneBehavior`] such as
+/// [`ComponentCloneBehavior::clone`].
+pub trait SharedComponent: TypePath + Sized + Send + Sync + 'static {
     type Mutability: ComponentMutability;
 
     const STORAGE_TYPE: StorageType;
@@ -66,7 +377,7 @@ pub trait SharedComponent: Pod + TypePath + Sized + Send + Sync + 'static {
 
     fn register_required_components(
         _component_id: ComponentId,
-        _required_components: &mut RequiredComponentsRegistrator<'_, '_>,
+        _required_components: &mut RequiredComponentsRegistrator<'_>,
     ) {
     }
 
@@ -0,0 +1,82 @@
+use crate::{
+    component::{Immutable, SharedComponent, StorageType},
+    world::{Bundle, EntityWorldMut, World},
+};
+use bevy_ecs::entity::Entity;
+
+pub use bevy_ecs::hierarchy::ChildOf;
+
+// `ChildOf` only holds its parent, so guests can insert it to update the host's `Children`.
+// Sharing it relies on the guest and host building the same `bevy_ecs` version.
+impl SharedComponent for ChildOf {
+    type Mutability = Immutable;
+
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+}
+
+impl World {
+    /// Returns the children of `entity`, or `None` if it doesn't exist or has no children.
+    ///
+    /// [`Children`](bevy_ecs::hierarchy::Children) is allocated by the host and kept up to date by its [`ChildOf`] hooks,
+    /// so its entities are copied out instead of shared.
+    pub fn children(&self, entity: Entity) -> Option<Vec<Entity>> {
+        let mut entities: Vec<u64> = Vec::new();
+        loop {
+            let mut len = 0;
+            let success = unsafe {
+                bevy_mod_ffi_guest_sys::world::bevy_world_entity_children(
+                    self.ptr,
+                    entity.to_bits(),
+                    entities.as_mut_ptr(),
+                    entities.len(),
+                    &mut len,
+                )
+            };
+            if !success {
+                return None;
+            }
+
+            if len <= entities.len() {
+                entities.truncate(len);
+                return Some(entities.into_iter().map(Entity::from_bits).collect());
+            }
+            entities.resize(len, 0);
+        }
+    }
+}
+
+/// Spawns children of an entity, from [`EntityWorldMut::with_children`].
+pub struct ChildSpawner<'w> {
+    world: &'w mut World,
+    parent: Entity,
+}
+
+impl ChildSpawner<'_> {
+    pub fn target_entity(&self) -> Entity {
+        self.parent
+    }
+
+    pub fn spawn<B: Bundle>(&mut self, bundle: B) -> EntityWorldMut<'_> {
+        self.world.spawn((bundle, ChildOf(self.parent)))
+    }
+}
+
+impl EntityWorldMut<'_> {
+    pub fn with_children(mut self, f: impl FnOnce(&mut ChildSpawner)) -> Self {
+        let parent = self.id();
+        self.world_scope(|world| f(&mut ChildSpawner { world, parent }));
+        self
+    }
+
+    pub fn add_child(mut self, child: Entity) -> Self {
+        let parent = self.id();
+        self.world_scope(|world| {
+            world.entity_mut(child).insert(ChildOf(parent));
+        });
+        self
+    }
+
+    pub fn set_parent(self, parent: Entity) -> Self {
+        self.insert(ChildOf(parent))
+    }
+}
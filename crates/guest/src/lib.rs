@@ -1,7 +1,11 @@
 pub mod component;
 
+pub mod hierarchy;
+
 pub mod query;
 
+pub mod relationship;
+
 pub mod system;
 
 pub mod world;
@@ -22,15 +26,20 @@ pub mod prelude {
         RequiredComponentsRegistrator, SharedComponent, StorageType,
     };
 
-    pub use crate::query::{Query, QueryBuilder, With, Without};
+    pub use crate::hierarchy::{ChildOf, ChildSpawner};
+
+    pub use crate::query::{Populated, Query, QueryBuilder, QueryState, Single, With, Without};
+
+    pub use crate::relationship::{Relationship, RelationshipTarget};
 
     pub use crate::system::{
-        EntityObserverSystem, IntoEntityObserverSystem, IntoObserverSystem, IntoSystem,
-        ObserverSystem, On, OnEntity, SharedEvent, System, SystemParam, SystemRef, SystemState,
+        EntityObserverSystem, IntoEntityObserverSystem, IntoObserverSystem, IntoSystem, Local,
+        ObserverSystem, On, OnEntity, ParamSet, SharedEvent, System, SystemParam, SystemRef,
+        SystemState,
     };
 
-    pub use crate::world::{DeferredWorld, World};
+    pub use crate::world::{Bundle, DeferredWorld, FromWorld, World};
 
     #[cfg(feature = "macros")]
-    pub use bevy_mod_ffi_macros::SharedComponent;
+    pub use bevy_mod_ffi_macros::{Bundle, QueryData, SharedComponent, SystemParam};
 }
@@ -1,25 +1,37 @@
 use super::{QueryData, QueryFilter, QueryState};
 use crate::world::World;
 use bevy_ecs::component::ComponentId;
-use bevy_mod_ffi_core::query_builder;
+use bevy_mod_ffi_core::{QueryTerm, QueryTermKind, query, query_builder};
 use bevy_mod_ffi_guest_sys;
 use bevy_reflect::TypePath;
-use std::{marker::PhantomData, mem};
+use std::{ffi::CString, marker::PhantomData, mem};
 
 pub struct QueryBuilder<'w, D = (), F = ()> {
-    pub(crate) ptr: *mut query_builder,
-    world: &'w mut World,
+    target: BuilderTarget<'w>,
     _marker: PhantomData<(D, F)>,
 }
 
+/// Where a [`QueryBuilder`] adds its terms.
+enum BuilderTarget<'w> {
+    /// A host query builder, which is built into a [`QueryState`].
+    Host {
+        ptr: *mut query_builder,
+        world: &'w mut World,
+    },
+    /// The filter of a query lens, whose components are looked up through the query it narrows.
+    Lens {
+        query_ptr: *mut query,
+        terms: Vec<QueryTerm>,
+    },
+}
+
 impl<'w, D: QueryData, F: QueryFilter> QueryBuilder<'w, D, F> {
     pub fn new(world: &'w mut World) -> Self {
         let ptr =
             unsafe { bevy_mod_ffi_guest_sys::query::builder::bevy_query_builder_new(world.ptr) };
 
         let mut me = Self {
-            ptr,
-            world,
+            target: BuilderTarget::Host { ptr, world },
             _marker: PhantomData,
         };
 
@@ -29,75 +41,141 @@ impl<'w, D: QueryData, F: QueryFilter> QueryBuilder<'w, D, F> {
         me
     }
 
-    pub fn with_ref_id(&mut self, component_id: ComponentId) -> &mut Self {
-        unsafe {
-            bevy_mod_ffi_guest_sys::query::builder::bevy_query_builder_with_ref(
-                self.ptr,
-                component_id.index(),
-            )
+    /// Collects the terms of `F`, looking up their components through the query at `query_ptr`.
+    pub(crate) fn filter_terms(query_ptr: *mut query) -> Vec<QueryTerm> {
+        let mut me = Self {
+            target: BuilderTarget::Lens {
+                query_ptr,
+                terms: Vec::new(),
+            },
+            _marker: PhantomData,
         };
 
+        F::filter(me.transmute());
+
+        match &mut me.target {
+            BuilderTarget::Lens { terms, .. } => mem::take(terms),
+            BuilderTarget::Host { .. } => unreachable!(),
+        }
+    }
+
+    fn add_term(&mut self, component_id: ComponentId, kind: QueryTermKind) {
+        match &mut self.target {
+            BuilderTarget::Host { ptr, .. } => {
+                let (ptr, id) = (*ptr, component_id.index());
+                unsafe {
+                    match kind {
+                        QueryTermKind::Ref => {
+                            bevy_mod_ffi_guest_sys::query::builder::bevy_query_builder_with_ref(
+                                ptr, id,
+                            )
+                        }
+                        QueryTermKind::Mut => {
+                            bevy_mod_ffi_guest_sys::query::builder::bevy_query_builder_with_mut(
+                                ptr, id,
+                            )
+                        }
+                        QueryTermKind::With => {
+                            bevy_mod_ffi_guest_sys::query::builder::bevy_query_builder_with(ptr, id)
+                        }
+                        QueryTermKind::Without => {
+                            bevy_mod_ffi_guest_sys::query::builder::bevy_query_builder_without(
+                                ptr, id,
+                            )
+                        }
+                    }
+                }
+            }
+            BuilderTarget::Lens { terms, .. } => terms.push(QueryTerm {
+                component_id: component_id.index(),
+                kind,
+            }),
+        }
+    }
+
+    fn component_id<T: TypePath>(&self) -> ComponentId {
+        match &self.target {
+            BuilderTarget::Host { world, .. } => world.get_component_id::<T>(),
+            BuilderTarget::Lens { query_ptr, .. } => {
+                let type_path_cstring = CString::new(T::type_path()).unwrap();
+                let type_path_bytes = type_path_cstring.as_bytes_with_nul();
+
+                let mut id: usize = 0;
+                let success = unsafe {
+                    bevy_mod_ffi_guest_sys::query::bevy_query_get_component_id(
+                        *query_ptr,
+                        type_path_bytes.as_ptr(),
+                        type_path_bytes.len(),
+                        &mut id,
+                    )
+                };
+                success.then(|| ComponentId::new(id))
+            }
+        }
+        .unwrap()
+    }
+
+    pub fn with_ref_id(&mut self, component_id: ComponentId) -> &mut Self {
+        self.add_term(component_id, QueryTermKind::Ref);
         self
     }
 
     pub fn with_ref<T: TypePath>(&mut self) -> &mut Self {
-        let component_id = self.world.get_component_id::<T>().unwrap();
+        let component_id = self.component_id::<T>();
         self.with_ref_id(component_id)
     }
 
     pub fn with_mut_id(&mut self, component_id: ComponentId) -> &mut Self {
-        unsafe {
-            bevy_mod_ffi_guest_sys::query::builder::bevy_query_builder_with_mut(
-                self.ptr,
-                component_id.index(),
-            )
-        };
-
+        self.add_term(component_id, QueryTermKind::Mut);
         self
     }
 
     pub fn with_mut<T: TypePath>(&mut self) -> &mut Self {
-        let component_id = self.world.get_component_id::<T>().unwrap();
+        let component_id = self.component_id::<T>();
         self.with_mut_id(component_id)
     }
 
     pub fn with_id(&mut self, component_id: ComponentId) -> &mut Self {
-        unsafe {
-            bevy_mod_ffi_guest_sys::query::builder::bevy_query_builder_with(
-                self.ptr,
-                component_id.index(),
-            )
-        };
-
+        self.add_term(component_id, QueryTermKind::With);
         self
     }
 
     pub fn with<T: TypePath>(&mut self) -> &mut Self {
-        let component_id = self.world.get_component_id::<T>().unwrap();
+        let component_id = self.component_id::<T>();
         self.with_id(component_id)
     }
 
     pub fn without_id(&mut self, component_id: ComponentId) -> &mut Self {
-        unsafe {
-            bevy_mod_ffi_guest_sys::query::builder::bevy_query_builder_without(
-                self.ptr,
-                component_id.index(),
-            )
-        };
-
+        self.add_term(component_id, QueryTermKind::Without);
         self
     }
 
     pub fn without<T: TypePath>(&mut self) -> &mut Self {
-        let component_id = self.world.get_component_id::<T>().unwrap();
+        let component_id = self.component_id::<T>();
         self.without_id(component_id)
     }
 
-    pub fn build(self) -> QueryState<D, F> {
-        let ptr =
-            unsafe { bevy_mod_ffi_guest_sys::query::builder::bevy_query_builder_build(self.ptr) };
+    pub fn build(mut self) -> QueryState<D, F> {
+        let BuilderTarget::Host { ptr, world } = &mut self.target else {
+            unreachable!("Query lens filters aren't built into states");
+        };
+        let data_state = D::build_state(world);
+
+        // The host consumes the builder, so it must not be dropped again.
+        let ptr = unsafe { bevy_mod_ffi_guest_sys::query::builder::bevy_query_builder_build(*ptr) };
+        mem::forget(self);
 
-        QueryState::from_raw(ptr, D::build_state(self.world))
+        QueryState::from_raw(ptr, data_state)
+    }
+
+    /// Returns the host query builder, which the caller must consume.
+    pub(crate) fn into_raw(self) -> *mut query_builder {
+        let BuilderTarget::Host { ptr, .. } = &self.target else {
+            unreachable!("Query lens filters have no host builder");
+        };
+        let ptr = *ptr;
+        mem::forget(self);
+        ptr
     }
 
     pub fn transmute<F2, D2>(&mut self) -> &mut QueryBuilder<'w, D2, F2> {
@@ -107,6 +185,8 @@ impl<'w, D: QueryData, F: QueryFilter> QueryBuilder<'w, D, F> {
 
 impl<D, F> Drop for QueryBuilder<'_, D, F> {
     fn drop(&mut self) {
-        unsafe { bevy_mod_ffi_guest_sys::query::builder::bevy_query_builder_drop(self.ptr) }
+        if let BuilderTarget::Host { ptr, .. } = self.target {
+            unsafe { bevy_mod_ffi_guest_sys::query::builder::bevy_query_builder_drop(ptr) }
+        }
     }
 }
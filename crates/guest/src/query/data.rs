@@ -1,10 +1,11 @@
 use crate::{
+    component::{Mutable, SharedComponent},
     query::QueryBuilder,
     world::{FilteredEntityMut, World},
 };
 use bevy_ecs::{component::ComponentId, entity::Entity};
-use bevy_reflect::TypePath;
-use bytemuck::Pod;
+use bevy_mod_ffi_core::FetchComponent;
+use std::{any::TypeId, ptr::NonNull, slice};
 
 pub trait QueryData: Sized {
     type Item<'w, 's>;
@@ -18,6 +19,50 @@ pub trait QueryData: Sized {
         entity: &mut FilteredEntityMut<'w>,
         state: &'s mut Self::State,
     ) -> Self::Item<'w, 's>;
+
+    /// Returns the id of component `C` if it is fetched by this query data.
+    fn component_id<C: 'static>(state: &Self::State) -> Option<ComponentId> {
+        let _ = state;
+        None
+    }
+
+    /// Derives this query data's state from the state of `Source`, returning `None`
+    /// if `Source` doesn't fetch every component needed with at least the same access.
+    fn transmute_state<Source: QueryData>(source: &Source::State) -> Option<Self::State>;
+
+    /// Pushes the components this query data reads from each entity during batched iteration.
+    fn fetches(state: &Self::State, fetches: &mut Vec<FetchComponent>);
+
+    /// Builds an item from the component pointers requested by [`QueryData::fetches`].
+    ///
+    /// # Safety
+    /// `components` must yield valid pointers in the same order they were pushed by
+    /// [`QueryData::fetches`], borrowed for `'w`.
+    unsafe fn from_fetch<'w, 's>(
+        entity: Entity,
+        components: &mut slice::Iter<'_, *mut u8>,
+        state: &'s mut Self::State,
+    ) -> Self::Item<'w, 's>;
+}
+
+/// Query data that only reads components, so its items may be held at the same time.
+///
+/// # Safety
+/// Implementors must only push immutable fetches in [`QueryData::fetches`].
+pub unsafe trait ReadOnlyQueryData: QueryData {}
+
+/// Query data that can be read as contiguous table columns.
+pub trait ChunkData: QueryData {
+    type Chunk<'w>;
+
+    /// Builds a chunk of `len` rows from the column pointers requested by [`QueryData::fetches`].
+    ///
+    /// # Safety
+    /// Each column must point to `len` contiguous, initialized components borrowed for `'w`.
+    unsafe fn from_columns<'w>(
+        columns: &mut slice::Iter<'_, *mut u8>,
+        len: usize,
+    ) -> Self::Chunk<'w>;
 }
 
 impl QueryData for Entity {
@@ -34,8 +79,24 @@ impl QueryData for Entity {
     ) -> Self::Item<'w, 's> {
         entity.id()
     }
+
+    fn transmute_state<Source: QueryData>(_source: &Source::State) -> Option<Self::State> {
+        Some(())
+    }
+
+    fn fetches(_state: &Self::State, _fetches: &mut Vec<FetchComponent>) {}
+
+    unsafe fn from_fetch<'w, 's>(
+        entity: Entity,
+        _components: &mut slice::Iter<'_, *mut u8>,
+        _state: &'s mut Self::State,
+    ) -> Self::Item<'w, 's> {
+        entity
+    }
 }
 
+unsafe impl ReadOnlyQueryData for Entity {}
+
 impl QueryData for () {
     type Item<'w, 's> = ();
     type State = ();
@@ -49,16 +110,39 @@ impl QueryData for () {
         _state: &'s mut Self::State,
     ) -> Self::Item<'w, 's> {
     }
+
+    fn transmute_state<Source: QueryData>(_source: &Source::State) -> Option<Self::State> {
+        Some(())
+    }
+
+    fn fetches(_state: &Self::State, _fetches: &mut Vec<FetchComponent>) {}
+
+    unsafe fn from_fetch<'w, 's>(
+        _entity: Entity,
+        _components: &mut slice::Iter<'_, *mut u8>,
+        _state: &'s mut Self::State,
+    ) -> Self::Item<'w, 's> {
+    }
 }
 
-impl<T: TypePath + Pod + 'static> QueryData for &T {
+unsafe impl ReadOnlyQueryData for () {}
+
+impl<T: SharedComponent> QueryData for &T {
     type Item<'w, 's> = &'w T;
     type State = ComponentId;
 
+    fn component_id<C: 'static>(state: &Self::State) -> Option<ComponentId> {
+        (TypeId::of::<C>() == TypeId::of::<T>()).then_some(*state)
+    }
+
     fn build_query(builder: &mut QueryBuilder) {
         builder.with_ref::<T>();
     }
 
+    fn transmute_state<Source: QueryData>(source: &Source::State) -> Option<Self::State> {
+        Source::component_id::<T>(source)
+    }
+
     fn build_state(world: &mut World) -> Self::State {
         world
             .get_component_id_from_type_path(T::type_path())
@@ -72,16 +156,61 @@ impl<T: TypePath + Pod + 'static> QueryData for &T {
         let ptr = entity.get_by_id(*state).unwrap();
         unsafe { ptr.deref() }
     }
+
+    fn fetches(state: &Self::State, fetches: &mut Vec<FetchComponent>) {
+        fetches.push(FetchComponent {
+            component_id: state.index(),
+            is_mut: false,
+        });
+    }
+
+    unsafe fn from_fetch<'w, 's>(
+        _entity: Entity,
+        components: &mut slice::Iter<'_, *mut u8>,
+        _state: &'s mut Self::State,
+    ) -> Self::Item<'w, 's> {
+        let ptr = NonNull::new(*components.next().unwrap()).unwrap();
+        unsafe { ptr.cast::<T>().as_ref() }
+    }
 }
 
-impl<T: TypePath + Pod + 'static> QueryData for &mut T {
+unsafe impl<T: SharedComponent> ReadOnlyQueryData for &T {}
+
+impl<T: SharedComponent> ChunkData for &T {
+    type Chunk<'w> = &'w [T];
+
+    unsafe fn from_columns<'w>(
+        columns: &mut slice::Iter<'_, *mut u8>,
+        len: usize,
+    ) -> Self::Chunk<'w> {
+        let ptr = NonNull::new(*columns.next().unwrap()).unwrap();
+        unsafe { slice::from_raw_parts(ptr.cast::<T>().as_ptr(), len) }
+    }
+}
+
+impl<T: SharedComponent<Mutability = Mutable>> QueryData for &mut T {
     type Item<'w, 's> = &'w mut T;
     type State = ComponentId;
 
+    fn component_id<C: 'static>(state: &Self::State) -> Option<ComponentId> {
+        (TypeId::of::<C>() == TypeId::of::<T>()).then_some(*state)
+    }
+
     fn build_query(builder: &mut QueryBuilder) {
         builder.with_mut::<T>();
     }
 
+    fn transmute_state<Source: QueryData>(source: &Source::State) -> Option<Self::State> {
+        let component_id = Source::component_id::<T>(source)?;
+
+        let mut fetches = Vec::new();
+        Source::fetches(source, &mut fetches);
+        fetches
+            .iter()
+            .any(|fetch| fetch.component_id == component_id.index() && fetch.is_mut)
+            .then_some(component_id)
+    }
+
     fn build_state(world: &mut World) -> Self::State {
         world
             .get_component_id_from_type_path(T::type_path())
@@ -95,6 +224,34 @@ impl<T: TypePath + Pod + 'static> QueryData for &mut T {
         let ptr = entity.get_mut_by_id(*state).unwrap();
         unsafe { ptr.deref_mut() }
     }
+
+    fn fetches(state: &Self::State, fetches: &mut Vec<FetchComponent>) {
+        fetches.push(FetchComponent {
+            component_id: state.index(),
+            is_mut: true,
+        });
+    }
+
+    unsafe fn from_fetch<'w, 's>(
+        _entity: Entity,
+        components: &mut slice::Iter<'_, *mut u8>,
+        _state: &'s mut Self::State,
+    ) -> Self::Item<'w, 's> {
+        let ptr = NonNull::new(*components.next().unwrap()).unwrap();
+        unsafe { ptr.cast::<T>().as_mut() }
+    }
+}
+
+impl<T: SharedComponent<Mutability = Mutable>> ChunkData for &mut T {
+    type Chunk<'w> = &'w mut [T];
+
+    unsafe fn from_columns<'w>(
+        columns: &mut slice::Iter<'_, *mut u8>,
+        len: usize,
+    ) -> Self::Chunk<'w> {
+        let ptr = NonNull::new(*columns.next().unwrap()).unwrap();
+        unsafe { slice::from_raw_parts_mut(ptr.cast::<T>().as_ptr(), len) }
+    }
 }
 
 macro_rules! impl_query_data_tuple {
@@ -113,6 +270,19 @@ macro_rules! impl_query_data_tuple {
                 ($($items::build_state(world)),+)
             }
 
+            fn component_id<Component: 'static>(state: &Self::State) -> Option<ComponentId> {
+                #[allow(non_snake_case)]
+                let ($($items),+) = state;
+                None
+                    $(
+                        .or_else(|| $items::component_id::<Component>($items))
+                    )+
+            }
+
+            fn transmute_state<Source: QueryData>(source: &Source::State) -> Option<Self::State> {
+                Some(($($items::transmute_state::<Source>(source)?),+))
+            }
+
             fn from_entity<'w, 's>(entity: &mut FilteredEntityMut<'w>, state: &'s mut Self::State) -> Self::Item<'w, 's> {
                 #[allow(non_snake_case)]
                 let ($($items),+) = state;
@@ -122,6 +292,38 @@ macro_rules! impl_query_data_tuple {
                     ),+
                 )
             }
+
+            fn fetches(state: &Self::State, fetches: &mut Vec<FetchComponent>) {
+                #[allow(non_snake_case)]
+                let ($($items),+) = state;
+                $(
+                    $items::fetches($items, fetches);
+                )+
+            }
+
+            unsafe fn from_fetch<'w, 's>(entity: Entity, components: &mut slice::Iter<'_, *mut u8>, state: &'s mut Self::State) -> Self::Item<'w, 's> {
+                #[allow(non_snake_case)]
+                let ($($items),+) = state;
+                (
+                    $(
+                        unsafe { $items::from_fetch(entity, components, $items) }
+                    ),+
+                )
+            }
+        }
+
+        unsafe impl<$($items: ReadOnlyQueryData),+> ReadOnlyQueryData for ($($items),+) {}
+
+        impl<$($items: ChunkData),+> ChunkData for ($($items),+) {
+            type Chunk<'w> = ($($items::Chunk<'w>),+);
+
+            unsafe fn from_columns<'w>(columns: &mut slice::Iter<'_, *mut u8>, len: usize) -> Self::Chunk<'w> {
+                (
+                    $(
+                        unsafe { $items::from_columns(columns, len) }
+                    ),+
+                )
+            }
         }
     };
 }
@@ -1,38 +1,132 @@
-use super::{QueryData, QueryFilter, QueryState};
-use crate::world::{FilteredEntityMut, World};
+use super::{QueryData, QueryFilter, QueryState, ReadOnlyQueryData};
+use crate::world::World;
 use bevy_ecs::entity::Entity;
-use bevy_mod_ffi_core::{filtered_entity_mut, query_iter};
+use bevy_mod_ffi_core::{FetchComponent, query_combination_iter, query_iter};
 use bevy_mod_ffi_guest_sys;
-use std::marker::PhantomData;
+use std::{array, marker::PhantomData, ptr};
+
+/// Number of entities fetched from the host per FFI call.
+const BATCH_SIZE: usize = 256;
 
 pub struct QueryIter<'w, 's, D: QueryData, F: QueryFilter> {
     iter_ptr: *mut query_iter,
     state: &'s mut D::State,
+    fetches: Vec<FetchComponent>,
+    entities: Box<[u64]>,
+    components: Box<[*mut u8]>,
+    len: usize,
+    position: usize,
     _marker: PhantomData<(&'w mut World, &'s QueryState<D, F>)>,
 }
 
 impl<'w, 's, D: QueryData, F: QueryFilter> QueryIter<'w, 's, D, F> {
     pub(crate) fn new(iter_ptr: *mut query_iter, state: &'s mut D::State) -> Self {
+        let mut fetches = Vec::new();
+        D::fetches(state, &mut fetches);
+
         QueryIter {
             iter_ptr,
+            entities: vec![0; BATCH_SIZE].into_boxed_slice(),
+            components: vec![ptr::null_mut(); BATCH_SIZE * fetches.len()].into_boxed_slice(),
+            fetches,
             state,
+            len: 0,
+            position: 0,
             _marker: PhantomData,
         }
     }
+
+    fn fetch_batch(&mut self) -> bool {
+        let mut len = 0;
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::query::iter::bevy_query_iter_next_batch(
+                self.iter_ptr,
+                self.fetches.as_ptr(),
+                self.fetches.len(),
+                self.entities.as_mut_ptr(),
+                self.components.as_mut_ptr(),
+                BATCH_SIZE,
+                &mut len,
+            )
+        };
+
+        self.len = if success { len } else { 0 };
+        self.position = 0;
+        self.len > 0
+    }
 }
 
 impl<'w, 's, D: QueryData, F: QueryFilter> Iterator for QueryIter<'w, 's, D, F> {
     type Item = D::Item<'w, 's>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut entity_id: u64 = 0;
-        let mut entity_ptr: *mut filtered_entity_mut = std::ptr::null_mut();
+        if self.position >= self.len && !self.fetch_batch() {
+            return None;
+        }
+
+        let row = self.position;
+        self.position += 1;
 
+        let state: &'s mut D::State =
+            unsafe { &mut *(self.state as *const D::State as *mut D::State) };
+
+        let entity = Entity::from_bits(self.entities[row]);
+        let fetches_len = self.fetches.len();
+        let mut components = self.components[row * fetches_len..(row + 1) * fetches_len].iter();
+
+        Some(unsafe { D::from_fetch(entity, &mut components, state) })
+    }
+}
+
+impl<D: QueryData, F: QueryFilter> Drop for QueryIter<'_, '_, D, F> {
+    fn drop(&mut self) {
+        unsafe { bevy_mod_ffi_guest_sys::query::iter::bevy_query_iter_drop(self.iter_ptr) };
+    }
+}
+
+pub struct QueryCombinationIter<'w, 's, D: QueryData, F: QueryFilter, const K: usize> {
+    iter_ptr: *mut query_combination_iter,
+    state: &'s mut D::State,
+    fetches: Vec<FetchComponent>,
+    entities: [u64; K],
+    components: Box<[*mut u8]>,
+    _marker: PhantomData<(&'w mut World, &'s QueryState<D, F>)>,
+}
+
+impl<'w, 's, D: QueryData, F: QueryFilter, const K: usize> QueryCombinationIter<'w, 's, D, F, K> {
+    pub(crate) fn new(iter_ptr: *mut query_combination_iter, state: &'s mut D::State) -> Self {
+        let mut fetches = Vec::new();
+        D::fetches(state, &mut fetches);
+
+        QueryCombinationIter {
+            iter_ptr,
+            entities: [0; K],
+            components: vec![ptr::null_mut(); K * fetches.len()].into_boxed_slice(),
+            fetches,
+            state,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Advances the iterator and returns the next combination of items.
+    ///
+    /// Unlike [`Iterator::next`], the returned items borrow the iterator, so combinations
+    /// of mutable query data can't alias each other.
+    pub fn fetch_next(&mut self) -> Option<[D::Item<'_, 's>; K]> {
+        unsafe { self.fetch_next_unchecked() }
+    }
+
+    /// # Safety
+    /// Items with mutable access must not be alive for the next call.
+    unsafe fn fetch_next_unchecked<'a>(&mut self) -> Option<[D::Item<'a, 's>; K]> {
         let success = unsafe {
-            bevy_mod_ffi_guest_sys::query::iter::bevy_query_iter_next(
+            bevy_mod_ffi_guest_sys::query::bevy_query_combination_iter_fetch_next(
                 self.iter_ptr,
-                &mut entity_id,
-                &mut entity_ptr,
+                self.fetches.as_ptr(),
+                self.fetches.len(),
+                self.entities.as_mut_ptr(),
+                K,
+                self.components.as_mut_ptr(),
             )
         };
 
@@ -40,19 +134,33 @@ impl<'w, 's, D: QueryData, F: QueryFilter> Iterator for QueryIter<'w, 's, D, F>
             return None;
         }
 
-        let state: &'s mut D::State =
-            unsafe { &mut *(self.state as *const D::State as *mut D::State) };
+        let fetches_len = self.fetches.len();
+        Some(array::from_fn(|index| {
+            let state: &'s mut D::State =
+                unsafe { &mut *(self.state as *const D::State as *mut D::State) };
 
-        let mut entity =
-            unsafe { FilteredEntityMut::from_ptr(Entity::from_bits(entity_id), entity_ptr) };
-        let item = D::from_entity(&mut entity, state);
+            let entity = Entity::from_bits(self.entities[index]);
+            let mut components =
+                self.components[index * fetches_len..(index + 1) * fetches_len].iter();
 
-        Some(item)
+            unsafe { D::from_fetch(entity, &mut components, state) }
+        }))
     }
 }
 
-impl<D: QueryData, F: QueryFilter> Drop for QueryIter<'_, '_, D, F> {
+impl<'w, 's, D: ReadOnlyQueryData, F: QueryFilter, const K: usize> Iterator
+    for QueryCombinationIter<'w, 's, D, F, K>
+{
+    type Item = [D::Item<'w, 's>; K];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Safety: read-only items never alias a mutable borrow.
+        unsafe { self.fetch_next_unchecked() }
+    }
+}
+
+impl<D: QueryData, F: QueryFilter, const K: usize> Drop for QueryCombinationIter<'_, '_, D, F, K> {
     fn drop(&mut self) {
-        unsafe { bevy_mod_ffi_guest_sys::query::iter::bevy_query_iter_drop(self.iter_ptr) };
+        unsafe { bevy_mod_ffi_guest_sys::query::bevy_query_combination_iter_drop(self.iter_ptr) };
     }
 }
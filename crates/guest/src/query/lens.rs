@@ -0,0 +1,41 @@
+use super::{Query, QueryData, QueryFilter};
+use crate::world::World;
+use bevy_mod_ffi_core::{query, query_lens};
+use bevy_mod_ffi_guest_sys;
+use std::{marker::PhantomData, ptr};
+
+/// A narrowed view of a [`Query`], created with [`Query::transmute_lens`] or [`Query::join`].
+pub struct QueryLens<'w, D: QueryData, F: QueryFilter = ()> {
+    ptr: *mut query_lens,
+    state: D::State,
+    _marker: PhantomData<(&'w mut World, F)>,
+}
+
+impl<'w, D: QueryData, F: QueryFilter> QueryLens<'w, D, F> {
+    pub(crate) fn new(ptr: *mut query_lens, state: D::State) -> Self {
+        Self {
+            ptr,
+            state,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn query(&mut self) -> Query<'_, '_, D, F> {
+        let mut query_ptr: *mut query = ptr::null_mut();
+
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::query::bevy_query_lens_query(self.ptr, &mut query_ptr)
+        };
+        if !success || query_ptr.is_null() {
+            panic!("Failed to create query from lens");
+        }
+
+        Query::new(query_ptr, &mut self.state)
+    }
+}
+
+impl<D: QueryData, F: QueryFilter> Drop for QueryLens<'_, D, F> {
+    fn drop(&mut self) {
+        unsafe { bevy_mod_ffi_guest_sys::query::bevy_query_lens_drop(self.ptr) }
+    }
+}
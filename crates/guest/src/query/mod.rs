@@ -1,20 +1,36 @@
 use crate::world::{FilteredEntityMut, World};
 use bevy_ecs::entity::Entity;
-use bevy_mod_ffi_core::{filtered_entity_mut, query, query_iter};
-use bevy_mod_ffi_guest_sys;
-use std::{marker::PhantomData, ptr};
+use bevy_mod_ffi_core::{
+    filtered_entity_mut, query, query_combination_iter, query_iter, query_lens,
+};
+use bevy_mod_ffi_guest_sys::{
+    self,
+    query::{ChunkClosure, CompareClosure},
+};
+use std::{
+    cmp::Ordering,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    ptr, slice,
+};
 
 mod builder;
 pub use builder::QueryBuilder;
 
 mod data;
-pub use data::QueryData;
+pub use data::{ChunkData, QueryData, ReadOnlyQueryData};
 
 mod filter;
 pub use filter::{QueryFilter, With, Without};
 
 mod iter;
-pub use iter::QueryIter;
+pub use iter::{QueryCombinationIter, QueryIter};
+
+mod lens;
+pub use lens::QueryLens;
+
+mod par_iter;
+pub use par_iter::QueryParIter;
 
 mod state;
 pub use state::QueryState;
@@ -22,7 +38,7 @@ pub use state::QueryState;
 pub struct Query<'w, 's, D: QueryData, F: QueryFilter = ()> {
     ptr: *mut query,
     state: &'s mut D::State,
-    _marker: PhantomData<(&'w mut World, &'s mut QueryState<D, F>)>,
+    _marker: PhantomData<(&'w mut World, F)>,
 }
 
 impl<'w, 's, D: QueryData, F: QueryFilter> Query<'w, 's, D, F> {
@@ -46,6 +62,237 @@ impl<'w, 's, D: QueryData, F: QueryFilter> Query<'w, 's, D, F> {
         QueryIter::new(iter_ptr, self.state)
     }
 
+    /// Returns an iterator over the query items sorted by component `C` with `compare`.
+    ///
+    /// The sort is stable and `C` must be fetched by the query data.
+    pub fn iter_sort_by<C: 'static>(
+        &mut self,
+        mut compare: impl FnMut(&C, &C) -> Ordering,
+    ) -> QueryIter<'_, '_, D, F> {
+        let component_id = D::component_id::<C>(self.state)
+            .expect("Sort component must be fetched by the query data");
+
+        let mut run_compare =
+            |a: *const u8, b: *const u8| unsafe { compare(&*(a as *const C), &*(b as *const C)) };
+        let mut closure: CompareClosure = &mut run_compare;
+
+        let mut iter_ptr: *mut query_iter = ptr::null_mut();
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::query::bevy_query_iter_mut_sort_by(
+                self.ptr,
+                component_id.index(),
+                &mut closure as *mut CompareClosure as *mut (),
+                bevy_mod_ffi_guest_sys::query::bevy_guest_run_compare,
+                &mut iter_ptr,
+            )
+        };
+        if !success || iter_ptr.is_null() {
+            panic!("Failed to create sorted query iterator");
+        }
+
+        QueryIter::new(iter_ptr, self.state)
+    }
+
+    /// Returns an iterator over the query items sorted by the key `f` extracts from component `C`.
+    ///
+    /// The sort is stable and `C` must be fetched by the query data.
+    pub fn iter_sort_by_key<C: 'static, K: Ord>(
+        &mut self,
+        mut f: impl FnMut(&C) -> K,
+    ) -> QueryIter<'_, '_, D, F> {
+        self.iter_sort_by::<C>(|a, b| f(a).cmp(&f(b)))
+    }
+
+    /// Returns an iterator over the query items in ascending [`Entity`] order.
+    pub fn iter_sort_by_entity(&mut self) -> QueryIter<'_, '_, D, F> {
+        let mut iter_ptr: *mut query_iter = ptr::null_mut();
+
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::query::bevy_query_iter_mut_sort_by_entity(
+                self.ptr,
+                &mut iter_ptr,
+            )
+        };
+        if !success || iter_ptr.is_null() {
+            panic!("Failed to create sorted query iterator");
+        }
+
+        QueryIter::new(iter_ptr, self.state)
+    }
+
+    pub fn iter_combinations_mut<const K: usize>(
+        &mut self,
+    ) -> QueryCombinationIter<'_, '_, D, F, K> {
+        let mut iter_ptr: *mut query_combination_iter = ptr::null_mut();
+
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::query::bevy_query_iter_combinations_mut(
+                self.ptr,
+                K,
+                &mut iter_ptr,
+            )
+        };
+        if !success || iter_ptr.is_null() {
+            panic!("Failed to create query combination iterator for K = {}", K);
+        }
+
+        QueryCombinationIter::new(iter_ptr, self.state)
+    }
+
+    /// Returns a lens over this query that fetches `NewD` instead.
+    ///
+    /// The lens matches the same entities as this query.
+    ///
+    /// # Panics
+    /// Panics if `NewD` accesses a component this query doesn't, or mutably accesses
+    /// a component this query only reads.
+    pub fn transmute_lens<NewD: QueryData>(&mut self) -> QueryLens<'_, NewD> {
+        self.transmute_lens_filtered::<NewD, ()>()
+    }
+
+    /// Equivalent to [`Query::transmute_lens`] with a `NewF` filter.
+    ///
+    /// Like in Bevy, the lens still matches the same entities as this query,
+    /// so [`With`] and [`Without`] terms in `NewF` don't narrow it.
+    ///
+    /// # Panics
+    /// Panics if `NewD` or `NewF` access a component this query doesn't.
+    pub fn transmute_lens_filtered<NewD: QueryData, NewF: QueryFilter>(
+        &mut self,
+    ) -> QueryLens<'_, NewD, NewF> {
+        let state = NewD::transmute_state::<D>(self.state).unwrap_or_else(|| {
+            panic!(
+                "Transmuted query data `{}` must be a subset of `{}`",
+                std::any::type_name::<NewD>(),
+                std::any::type_name::<D>()
+            )
+        });
+        let filter = QueryBuilder::<(), NewF>::filter_terms(self.ptr);
+
+        let mut lens_ptr: *mut query_lens = ptr::null_mut();
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::query::bevy_query_transmute_lens(
+                self.ptr,
+                filter.as_ptr(),
+                filter.len(),
+                &mut lens_ptr,
+            )
+        };
+        if !success || lens_ptr.is_null() {
+            panic!(
+                "Transmuted state for `({}, {})` attempts to access terms that are not allowed by `({}, {})`",
+                std::any::type_name::<NewD>(),
+                std::any::type_name::<NewF>(),
+                std::any::type_name::<D>(),
+                std::any::type_name::<F>()
+            );
+        }
+
+        QueryLens::new(lens_ptr, state)
+    }
+
+    /// Returns a lens over the entities matched by both this query and `other`,
+    /// fetching `NewD` from their combined access.
+    ///
+    /// # Panics
+    /// Panics if `NewD` accesses a component neither query does.
+    pub fn join<'a, OtherD: QueryData, NewD: QueryData>(
+        &'a mut self,
+        other: &'a mut Query<'_, '_, OtherD>,
+    ) -> QueryLens<'a, NewD> {
+        self.join_filtered::<OtherD, (), NewD, ()>(other)
+    }
+
+    /// Equivalent to [`Query::join`] with a filtered `other` query and a `NewF` filter.
+    ///
+    /// Like in Bevy, [`With`] and [`Without`] terms in `NewF` don't narrow the joined lens.
+    ///
+    /// # Panics
+    /// Panics if `NewD` or `NewF` access a component neither query does.
+    pub fn join_filtered<
+        'a,
+        OtherD: QueryData,
+        OtherF: QueryFilter,
+        NewD: QueryData,
+        NewF: QueryFilter,
+    >(
+        &'a mut self,
+        other: &'a mut Query<'_, '_, OtherD, OtherF>,
+    ) -> QueryLens<'a, NewD, NewF> {
+        let source = (self.state.clone(), other.state.clone());
+        let state = NewD::transmute_state::<(D, OtherD)>(&source).unwrap_or_else(|| {
+            panic!(
+                "Joined query data `{}` must be a subset of `({}, {})`",
+                std::any::type_name::<NewD>(),
+                std::any::type_name::<D>(),
+                std::any::type_name::<OtherD>()
+            )
+        });
+        let filter = QueryBuilder::<(), NewF>::filter_terms(self.ptr);
+
+        let mut lens_ptr: *mut query_lens = ptr::null_mut();
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::query::bevy_query_join(
+                self.ptr,
+                other.ptr,
+                filter.as_ptr(),
+                filter.len(),
+                &mut lens_ptr,
+            )
+        };
+        if !success || lens_ptr.is_null() {
+            panic!(
+                "Joined state for `({}, {})` attempts to access terms that are not allowed by `({}, {})` and `({}, {})`",
+                std::any::type_name::<NewD>(),
+                std::any::type_name::<NewF>(),
+                std::any::type_name::<D>(),
+                std::any::type_name::<F>(),
+                std::any::type_name::<OtherD>(),
+                std::any::type_name::<OtherF>()
+            );
+        }
+
+        QueryLens::new(lens_ptr, state)
+    }
+
+    pub fn par_iter_mut(&mut self) -> QueryParIter<'_, '_, D, F> {
+        QueryParIter::new(self.ptr, self.state)
+    }
+
+    /// Runs `f` for each run of matched entities stored contiguously in a table,
+    /// with the queried components passed as slices.
+    pub fn for_each_chunk<Func>(&mut self, mut f: Func)
+    where
+        D: ChunkData,
+        Func: FnMut(&[Entity], D::Chunk<'_>),
+    {
+        let mut fetches = Vec::new();
+        D::fetches(self.state, &mut fetches);
+
+        let mut entities = Vec::new();
+        let mut run_chunk = |entity_bits: &[u64], columns: *const *mut u8| {
+            entities.clear();
+            entities.extend(entity_bits.iter().map(|bits| Entity::from_bits(*bits)));
+
+            let columns = unsafe { slice::from_raw_parts(columns, fetches.len()) };
+            let chunk = unsafe { D::from_columns(&mut columns.iter(), entities.len()) };
+            f(&entities, chunk);
+        };
+        let mut closure: ChunkClosure = &mut run_chunk;
+
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::query::bevy_query_for_each_chunk(
+                self.ptr,
+                fetches.as_ptr(),
+                fetches.len(),
+                &mut closure as *mut ChunkClosure as *mut (),
+                bevy_mod_ffi_guest_sys::query::bevy_guest_run_chunk,
+            )
+        };
+
+        assert!(success, "Failed to iterate query chunks");
+    }
+
     pub fn get_mut(&mut self, entity: Entity) -> Option<D::Item<'_, '_>> {
         let mut ptr: *mut filtered_entity_mut = ptr::null_mut();
 
@@ -79,3 +326,56 @@ impl<D: QueryData, F: QueryFilter> Drop for Query<'_, '_, D, F> {
         unsafe { bevy_mod_ffi_guest_sys::query::bevy_query_drop(self.ptr) }
     }
 }
+
+/// A system param for the item of a query that matches exactly one entity.
+///
+/// The system is skipped if the query matches zero or more than one entity.
+pub struct Single<'w, 's, D: QueryData, F: QueryFilter = ()> {
+    pub(crate) item: D::Item<'w, 's>,
+    pub(crate) _filter: PhantomData<F>,
+}
+
+impl<'w, 's, D: QueryData, F: QueryFilter> Single<'w, 's, D, F> {
+    pub fn into_inner(self) -> D::Item<'w, 's> {
+        self.item
+    }
+}
+
+impl<'w, 's, D: QueryData, F: QueryFilter> Deref for Single<'w, 's, D, F> {
+    type Target = D::Item<'w, 's>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.item
+    }
+}
+
+impl<D: QueryData, F: QueryFilter> DerefMut for Single<'_, '_, D, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.item
+    }
+}
+
+/// A system param for a [`Query`] that matches at least one entity.
+///
+/// The system is skipped if the query matches no entities.
+pub struct Populated<'w, 's, D: QueryData, F: QueryFilter = ()>(pub(crate) Query<'w, 's, D, F>);
+
+impl<'w, 's, D: QueryData, F: QueryFilter> Populated<'w, 's, D, F> {
+    pub fn into_inner(self) -> Query<'w, 's, D, F> {
+        self.0
+    }
+}
+
+impl<'w, 's, D: QueryData, F: QueryFilter> Deref for Populated<'w, 's, D, F> {
+    type Target = Query<'w, 's, D, F>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<D: QueryData, F: QueryFilter> DerefMut for Populated<'_, '_, D, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
@@ -0,0 +1,73 @@
+use super::{Query, QueryData, QueryFilter};
+use bevy_ecs::entity::Entity;
+use bevy_mod_ffi_core::query;
+use bevy_mod_ffi_guest_sys::{self, query::BatchClosure};
+use std::{marker::PhantomData, slice};
+
+/// Default number of entities passed to each parallel batch.
+const DEFAULT_BATCH_SIZE: usize = 256;
+
+pub struct QueryParIter<'w, 's, D: QueryData, F: QueryFilter> {
+    ptr: *mut query,
+    state: &'s D::State,
+    batch_size: usize,
+    _marker: PhantomData<&'w mut Query<'w, 's, D, F>>,
+}
+
+impl<'w, 's, D: QueryData, F: QueryFilter> QueryParIter<'w, 's, D, F> {
+    pub(crate) fn new(ptr: *mut query, state: &'s D::State) -> Self {
+        Self {
+            ptr,
+            state,
+            batch_size: DEFAULT_BATCH_SIZE,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sets the maximum number of entities handled by a single task.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Runs `f` on each matched entity, spreading batches over the host's compute task pool.
+    pub fn for_each<Func>(self, f: Func)
+    where
+        Func: Fn(D::Item<'_, '_>) + Send + Sync,
+        D::State: Sync,
+    {
+        let mut fetches = Vec::new();
+        D::fetches(self.state, &mut fetches);
+        let fetches_len = fetches.len();
+
+        let state = self.state;
+        let run_batch = |entity_bits: &[u64], components: *const *mut u8| {
+            let components =
+                unsafe { slice::from_raw_parts(components, entity_bits.len() * fetches_len) };
+            let mut state = state.clone();
+
+            for (row, bits) in entity_bits.iter().enumerate() {
+                let mut row_components =
+                    components[row * fetches_len..(row + 1) * fetches_len].iter();
+                let item = unsafe {
+                    D::from_fetch(Entity::from_bits(*bits), &mut row_components, &mut state)
+                };
+                f(item);
+            }
+        };
+        let closure: BatchClosure = &run_batch;
+
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::query::par_iter::bevy_query_par_for_each(
+                self.ptr,
+                fetches.as_ptr(),
+                fetches_len,
+                self.batch_size,
+                &closure as *const BatchClosure as *mut (),
+                bevy_mod_ffi_guest_sys::query::par_iter::bevy_guest_run_batch,
+            )
+        };
+
+        assert!(success, "Failed to iterate query in parallel");
+    }
+}
@@ -1,5 +1,5 @@
 use super::{QueryBuilder, QueryData, QueryFilter, QueryIter};
-use crate::world::World;
+use crate::world::{FromWorld, World};
 use bevy_mod_ffi_core::{query_iter, query_state};
 use bevy_mod_ffi_guest_sys;
 use std::{marker::PhantomData, ptr};
@@ -28,6 +28,20 @@ impl<D: QueryData, F: QueryFilter> QueryState<D, F> {
         self.ptr
     }
 
+    /// Updates the cached set of matched archetypes with any created since the last update.
+    ///
+    /// Iterating also performs this update, so calling it is only needed to refresh
+    /// the cache ahead of time.
+    pub fn update_archetypes(&mut self, world: &World) {
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::query::state::bevy_query_state_update_archetypes(
+                world.ptr, self.ptr,
+            )
+        };
+
+        assert!(success, "Failed to update query archetypes");
+    }
+
     pub fn iter_mut<'w, 's>(&'s mut self, world: &'w mut World) -> QueryIter<'w, 's, D, F> {
         let mut iter_ptr: *mut query_iter = ptr::null_mut();
         let success = unsafe {
@@ -46,6 +60,18 @@ impl<D: QueryData, F: QueryFilter> QueryState<D, F> {
     }
 }
 
+// Safety: the host query state is `Send + Sync` and is only accessed through `&mut self`
+// or together with a borrow of the world.
+unsafe impl<D: QueryData, F: QueryFilter> Send for QueryState<D, F> where D::State: Send {}
+
+unsafe impl<D: QueryData, F: QueryFilter> Sync for QueryState<D, F> where D::State: Sync {}
+
+impl<D: QueryData, F: QueryFilter> FromWorld for QueryState<D, F> {
+    fn from_world(world: &mut World) -> Self {
+        QueryState::new(world)
+    }
+}
+
 impl<D: QueryData, F: QueryFilter> Drop for QueryState<D, F> {
     fn drop(&mut self) {
         unsafe { bevy_mod_ffi_guest_sys::query::state::bevy_query_state_drop(self.ptr) }
@@ -0,0 +1,158 @@
+use crate::{
+    component::{HookContext, Mutable, RelationshipHookMode, SharedComponent},
+    world::{DeferredWorld, World},
+};
+use bevy_ecs::entity::Entity;
+
+/// A component on a source entity that points to a target entity.
+///
+/// The [`RelationshipTarget`] on the target is kept in sync by the hooks of this trait, which the
+/// `SharedComponent` derive sets for `#[relationship(relationship_target = T)]`.
+pub trait Relationship: SharedComponent {
+    type RelationshipTarget: RelationshipTarget<Relationship = Self>;
+
+    fn get(&self) -> Entity;
+
+    fn from(entity: Entity) -> Self;
+
+    fn on_insert(mut world: DeferredWorld<'_>, context: HookContext) {
+        let HookContext {
+            entity,
+            relationship_hook_mode,
+            ..
+        } = context;
+        match relationship_hook_mode {
+            RelationshipHookMode::Run => {}
+            RelationshipHookMode::Skip => return,
+            RelationshipHookMode::RunIfNotLinked => {
+                if Self::RelationshipTarget::LINKED_SPAWN {
+                    return;
+                }
+            }
+        }
+
+        let target = world.get::<Self>(entity).unwrap().get();
+        if target == entity || !world.contains_entity(target) {
+            world.commands().push(move |world: &mut World| {
+                if let Some(entity) = world.get_entity_mut(entity) {
+                    entity.remove::<Self>();
+                }
+            });
+            return;
+        }
+
+        world.commands().push(move |world: &mut World| {
+            if let Some(relationship_target) = world.get_mut::<Self::RelationshipTarget>(target) {
+                relationship_target.collection_mut_risky().push(entity);
+            } else if let Some(target) = world.get_entity_mut(target) {
+                target.insert(Self::RelationshipTarget::from_collection_risky(vec![
+                    entity,
+                ]));
+            }
+        });
+    }
+
+    fn on_replace(mut world: DeferredWorld<'_>, context: HookContext) {
+        let HookContext {
+            entity,
+            relationship_hook_mode,
+            ..
+        } = context;
+        match relationship_hook_mode {
+            RelationshipHookMode::Run => {}
+            RelationshipHookMode::Skip => return,
+            RelationshipHookMode::RunIfNotLinked => {
+                if Self::RelationshipTarget::LINKED_SPAWN {
+                    return;
+                }
+            }
+        }
+
+        let target = world.get::<Self>(entity).unwrap().get();
+        let Some(relationship_target) = world.get_mut::<Self::RelationshipTarget>(target) else {
+            return;
+        };
+        relationship_target
+            .collection_mut_risky()
+            .retain(|source| *source != entity);
+
+        // Another source may be added before the command runs, so emptiness is checked again
+        if relationship_target.is_empty() {
+            world.commands().push(move |world: &mut World| {
+                if world
+                    .get::<Self::RelationshipTarget>(target)
+                    .is_some_and(RelationshipTarget::is_empty)
+                {
+                    world
+                        .entity_mut(target)
+                        .remove::<Self::RelationshipTarget>();
+                }
+            });
+        }
+    }
+}
+
+/// A component on a target entity that collects the sources of a [`Relationship`].
+///
+/// Set with `#[relationship_target(relationship = R)]` on the `SharedComponent` derive. With
+/// `linked_spawn`, despawning the target also despawns its sources.
+pub trait RelationshipTarget: SharedComponent<Mutability = Mutable> {
+    const LINKED_SPAWN: bool;
+
+    type Relationship: Relationship<RelationshipTarget = Self>;
+
+    fn collection(&self) -> &Vec<Entity>;
+
+    /// Returns the sources mutably, which can leave them out of sync with their relationships.
+    fn collection_mut_risky(&mut self) -> &mut Vec<Entity>;
+
+    /// Creates the component from sources, which can leave them out of sync with their
+    /// relationships.
+    fn from_collection_risky(collection: Vec<Entity>) -> Self;
+
+    fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.collection().iter().copied()
+    }
+
+    fn len(&self) -> usize {
+        self.collection().len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.collection().is_empty()
+    }
+
+    fn on_replace(mut world: DeferredWorld<'_>, context: HookContext) {
+        if !matches!(context.relationship_hook_mode, RelationshipHookMode::Run) {
+            return;
+        }
+
+        let sources = world
+            .get::<Self>(context.entity)
+            .unwrap()
+            .collection()
+            .clone();
+        let mut commands = world.commands();
+        for source in sources {
+            commands.push(move |world: &mut World| {
+                if let Some(source) = world.get_entity_mut(source) {
+                    source.remove::<Self::Relationship>();
+                }
+            });
+        }
+    }
+
+    fn on_despawn(mut world: DeferredWorld<'_>, context: HookContext) {
+        let sources = world
+            .get::<Self>(context.entity)
+            .unwrap()
+            .collection()
+            .clone();
+        let mut commands = world.commands();
+        for source in sources {
+            commands.push(move |world: &mut World| {
+                world.despawn(source);
+            });
+        }
+    }
+}
@@ -2,7 +2,7 @@ use crate::{
     query::{QueryBuilder, QueryData, QueryFilter},
     world::World,
 };
-use bevy_mod_ffi_core::{dyn_system_param, param_builder, system_state};
+use bevy_mod_ffi_core::{dyn_system_param, param_builder, query_builder, system_state};
 use bevy_mod_ffi_guest_sys;
 use std::{mem, ptr};
 
@@ -25,13 +25,8 @@ impl ParamBuilder {
         Self { ptr: builder_ptr }
     }
 
-    pub fn add_query<D: QueryData + 'static, F: QueryFilter + 'static>(
-        &mut self,
-        world: &mut World,
-    ) {
-        let query_builder = QueryBuilder::<D, F>::new(world);
-        let query_ptr = query_builder.ptr;
-        mem::forget(query_builder);
+    pub fn add_query<D: QueryData, F: QueryFilter>(&mut self, world: &mut World) {
+        let query_ptr = Self::query_builder_ptr::<D, F>(world);
 
         let success = unsafe {
             bevy_mod_ffi_guest_sys::system::param::bevy_param_builder_add_query(self.ptr, query_ptr)
@@ -42,6 +37,38 @@ impl ParamBuilder {
         }
     }
 
+    pub fn add_single<D: QueryData, F: QueryFilter>(&mut self, world: &mut World) {
+        let query_ptr = Self::query_builder_ptr::<D, F>(world);
+
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::system::param::bevy_param_builder_add_single(
+                self.ptr, query_ptr,
+            )
+        };
+
+        if !success {
+            panic!("Failed to add single to param builder");
+        }
+    }
+
+    pub fn add_populated<D: QueryData, F: QueryFilter>(&mut self, world: &mut World) {
+        let query_ptr = Self::query_builder_ptr::<D, F>(world);
+
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::system::param::bevy_param_builder_add_populated(
+                self.ptr, query_ptr,
+            )
+        };
+
+        if !success {
+            panic!("Failed to add populated to param builder");
+        }
+    }
+
+    fn query_builder_ptr<D: QueryData, F: QueryFilter>(world: &mut World) -> *mut query_builder {
+        QueryBuilder::<D, F>::new(world).into_raw()
+    }
+
     pub fn add_commands(&mut self) {
         let success = unsafe {
             bevy_mod_ffi_guest_sys::system::param::bevy_param_builder_add_commands(self.ptr)
@@ -62,6 +89,30 @@ impl ParamBuilder {
         }
     }
 
+    /// Adds a param set whose members were each built into their own builder.
+    pub fn add_param_set(&mut self, members: Vec<ParamBuilder>) {
+        let member_ptrs: Vec<*mut param_builder> = members
+            .into_iter()
+            .map(|member| {
+                let ptr = member.ptr;
+                mem::forget(member);
+                ptr
+            })
+            .collect();
+
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::system::param::bevy_param_builder_add_param_set(
+                self.ptr,
+                member_ptrs.as_ptr(),
+                member_ptrs.len(),
+            )
+        };
+
+        if !success {
+            panic!("Failed to add param set to param builder");
+        }
+    }
+
     pub(crate) fn build(self, world: &mut World) -> *mut system_state {
         let mut state_ptr: *mut system_state = ptr::null_mut();
 
@@ -66,6 +66,12 @@ impl<'w, 's> Commands<'w, 's> {
     }
 }
 
+impl Drop for Commands<'_, '_> {
+    fn drop(&mut self) {
+        unsafe { bevy_mod_ffi_guest_sys::system::param::bevy_commands_drop(self.ptr) }
+    }
+}
+
 unsafe impl SystemParam for Commands<'_, '_> {
     type State = ();
     type Item<'w, 's> = Commands<'w, 's>;
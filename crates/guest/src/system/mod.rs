@@ -17,7 +17,7 @@ pub use observer::{
 };
 
 mod param;
-pub use param::SystemParam;
+pub use param::{Local, ParamSet, SystemParam};
 
 mod state;
 pub use state::{SystemRef, SystemState};
@@ -38,6 +38,12 @@ pub unsafe extern "C" fn bevy_guest_run_system(
     f(params_slice, input_ptr, output_ptr);
 }
 
+#[allow(clippy::missing_safety_doc)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_guest_drop_system(f_ptr: *mut ()) {
+    let _ = unsafe { Box::from_raw(f_ptr as *mut SystemClosure) };
+}
+
 pub trait SystemInput {}
 
 #[repr(transparent)]
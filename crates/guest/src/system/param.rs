@@ -1,11 +1,16 @@
 use super::{ParamBuilder, ParamCursor};
 use crate::{
-    query::{Query, QueryData, QueryFilter},
-    world::World,
+    query::{Populated, Query, QueryData, QueryFilter, Single},
+    world::{FilteredEntityMut, FromWorld, World},
 };
-use bevy_mod_ffi_core::query;
+use bevy_ecs::entity::Entity;
+use bevy_mod_ffi_core::{dyn_system_param, filtered_entity_mut, param_set, query};
 use bevy_mod_ffi_guest_sys;
-use std::ptr;
+use std::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    ptr, slice,
+};
 
 #[allow(clippy::missing_safety_doc)]
 pub unsafe trait SystemParam {
@@ -38,8 +43,9 @@ unsafe impl SystemParam for () {
 
 unsafe impl<D, F> SystemParam for Query<'_, '_, D, F>
 where
-    D: QueryData + 'static,
-    F: QueryFilter + 'static,
+    D: QueryData,
+    D::State: 'static,
+    F: QueryFilter,
 {
     type State = D::State;
     type Item<'w, 's> = Query<'w, 's, D, F>;
@@ -68,6 +74,252 @@ where
     }
 }
 
+unsafe impl<D, F> SystemParam for Single<'_, '_, D, F>
+where
+    D: QueryData,
+    D::State: 'static,
+    F: QueryFilter,
+{
+    type State = D::State;
+    type Item<'w, 's> = Single<'w, 's, D, F>;
+
+    fn build(world: &mut World, builder: &mut ParamBuilder) -> Self::State {
+        builder.add_single::<D, F>(world);
+        D::build_state(world)
+    }
+
+    unsafe fn get_param<'w, 's>(
+        state: &'s mut Self::State,
+        cursor: &mut ParamCursor<'_>,
+    ) -> Self::Item<'w, 's> {
+        let dyn_param_ptr = cursor.next().unwrap();
+        let mut entity_bits = 0;
+        let mut entity_ptr: *mut filtered_entity_mut = ptr::null_mut();
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::system::param::bevy_dyn_system_param_downcast_single(
+                dyn_param_ptr,
+                &mut entity_bits,
+                &mut entity_ptr,
+            )
+        };
+        if !success || entity_ptr.is_null() {
+            panic!("Failed to downcast DynSystemParam to Single");
+        }
+
+        let mut entity_mut =
+            unsafe { FilteredEntityMut::from_ptr(Entity::from_bits(entity_bits), entity_ptr) };
+        Single {
+            item: D::from_entity(&mut entity_mut, state),
+            _filter: PhantomData,
+        }
+    }
+}
+
+unsafe impl<D, F> SystemParam for Populated<'_, '_, D, F>
+where
+    D: QueryData,
+    D::State: 'static,
+    F: QueryFilter,
+{
+    type State = D::State;
+    type Item<'w, 's> = Populated<'w, 's, D, F>;
+
+    fn build(world: &mut World, builder: &mut ParamBuilder) -> Self::State {
+        builder.add_populated::<D, F>(world);
+        D::build_state(world)
+    }
+
+    unsafe fn get_param<'w, 's>(
+        state: &'s mut Self::State,
+        cursor: &mut ParamCursor<'_>,
+    ) -> Self::Item<'w, 's> {
+        let dyn_param_ptr = cursor.next().unwrap();
+        let mut query_ptr: *mut query = ptr::null_mut();
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::system::param::bevy_dyn_system_param_downcast_populated(
+                dyn_param_ptr,
+                &mut query_ptr,
+            )
+        };
+        if !success || query_ptr.is_null() {
+            panic!("Failed to downcast DynSystemParam to Populated");
+        }
+        Populated(Query::new(query_ptr, state))
+    }
+}
+
+/// A value local to a system, kept across runs of that system.
+pub struct Local<'s, T: FromWorld + 'static>(pub(crate) &'s mut T);
+
+impl<T: FromWorld + 'static> Deref for Local<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl<T: FromWorld + 'static> DerefMut for Local<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0
+    }
+}
+
+unsafe impl<T: FromWorld + 'static> SystemParam for Local<'_, T> {
+    type State = T;
+    type Item<'w, 's> = Local<'s, T>;
+
+    fn build(world: &mut World, _builder: &mut ParamBuilder) -> Self::State {
+        T::from_world(world)
+    }
+
+    unsafe fn get_param<'w, 's>(
+        state: &'s mut Self::State,
+        _cursor: &mut ParamCursor<'_>,
+    ) -> Self::Item<'w, 's> {
+        Local(state)
+    }
+}
+
+/// A set of system params with conflicting access, borrowed one at a time.
+pub struct ParamSet<'w, 's, T: SystemParam> {
+    ptr: *mut param_set,
+    state: &'s mut T::State,
+    _marker: PhantomData<&'w mut World>,
+}
+
+impl<T: SystemParam> ParamSet<'_, '_, T> {
+    /// Gets the host params of member `index`, passing them to `f` as a cursor.
+    fn get_member<R>(&mut self, index: usize, f: impl FnOnce(&mut ParamCursor<'_>) -> R) -> R {
+        let mut params_ptr: *mut *mut dyn_system_param = ptr::null_mut();
+        let mut params_len = 0;
+
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::system::param::bevy_param_set_get_mut(
+                self.ptr,
+                index,
+                &mut params_ptr,
+                &mut params_len,
+            )
+        };
+        if !success {
+            panic!("Failed to get param set member {}", index);
+        }
+
+        let params = unsafe { slice::from_raw_parts(params_ptr, params_len) };
+        let out = f(&mut ParamCursor::new(params));
+
+        unsafe {
+            bevy_mod_ffi_guest_sys::system::param::bevy_param_set_params_drop(
+                params_ptr, params_len,
+            )
+        };
+
+        out
+    }
+}
+
+impl<T: SystemParam> Drop for ParamSet<'_, '_, T> {
+    fn drop(&mut self) {
+        unsafe { bevy_mod_ffi_guest_sys::system::param::bevy_param_set_drop(self.ptr) }
+    }
+}
+
+macro_rules! impl_param_set {
+    ($(($param:ident, $get:ident, $index:tt)),*) => {
+        unsafe impl<$($param: SystemParam,)*> SystemParam for ParamSet<'_, '_, ($($param,)*)> {
+            type State = ($($param::State,)*);
+            type Item<'w, 's> = ParamSet<'w, 's, ($($param,)*)>;
+
+            fn build(world: &mut World, builder: &mut ParamBuilder) -> Self::State {
+                let mut members = Vec::new();
+                let state = ($(
+                    {
+                        let mut member = ParamBuilder::new();
+                        let state = $param::build(world, &mut member);
+                        members.push(member);
+                        state
+                    },
+                )*);
+                builder.add_param_set(members);
+                state
+            }
+
+            unsafe fn get_param<'w, 's>(
+                state: &'s mut Self::State,
+                cursor: &mut ParamCursor<'_>,
+            ) -> Self::Item<'w, 's> {
+                let dyn_param_ptr = cursor.next().unwrap();
+                let mut set_ptr: *mut param_set = ptr::null_mut();
+                let success = unsafe {
+                    bevy_mod_ffi_guest_sys::system::param::bevy_dyn_system_param_downcast_param_set(
+                        dyn_param_ptr,
+                        &mut set_ptr,
+                    )
+                };
+                if !success || set_ptr.is_null() {
+                    panic!("Failed to downcast DynSystemParam to ParamSet");
+                }
+
+                ParamSet {
+                    ptr: set_ptr,
+                    state,
+                    _marker: PhantomData,
+                }
+            }
+        }
+
+        impl<$($param: SystemParam,)*> ParamSet<'_, '_, ($($param,)*)> {
+            $(
+                /// Gets exclusive access to this member of the param set.
+                pub fn $get(&mut self) -> $param::Item<'_, '_> {
+                    let state = &mut self.state.$index as *mut $param::State;
+                    self.get_member($index, |cursor| unsafe { $param::get_param(&mut *state, cursor) })
+                }
+            )*
+        }
+    };
+}
+
+impl_param_set!((P0, p0, 0));
+impl_param_set!((P0, p0, 0), (P1, p1, 1));
+impl_param_set!((P0, p0, 0), (P1, p1, 1), (P2, p2, 2));
+impl_param_set!((P0, p0, 0), (P1, p1, 1), (P2, p2, 2), (P3, p3, 3));
+impl_param_set!(
+    (P0, p0, 0),
+    (P1, p1, 1),
+    (P2, p2, 2),
+    (P3, p3, 3),
+    (P4, p4, 4)
+);
+impl_param_set!(
+    (P0, p0, 0),
+    (P1, p1, 1),
+    (P2, p2, 2),
+    (P3, p3, 3),
+    (P4, p4, 4),
+    (P5, p5, 5)
+);
+impl_param_set!(
+    (P0, p0, 0),
+    (P1, p1, 1),
+    (P2, p2, 2),
+    (P3, p3, 3),
+    (P4, p4, 4),
+    (P5, p5, 5),
+    (P6, p6, 6)
+);
+impl_param_set!(
+    (P0, p0, 0),
+    (P1, p1, 1),
+    (P2, p2, 2),
+    (P3, p3, 3),
+    (P4, p4, 4),
+    (P5, p5, 5),
+    (P6, p6, 6),
+    (P7, p7, 7)
+);
+
 macro_rules! impl_system_param_tuple {
     ($($param:ident),*) => {
         #[allow(non_snake_case)]
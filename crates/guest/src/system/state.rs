@@ -1,4 +1,4 @@
-use super::{ParamBuilder, SystemParam, bevy_guest_run_system};
+use super::{ParamBuilder, SystemParam, bevy_guest_drop_system, bevy_guest_run_system};
 use crate::{
     system::{IntoSystem, ParamCursor, System, SystemClosure},
     world::World,
@@ -45,8 +45,9 @@ impl<P: SystemParam + 'static> SystemState<P> {
         let out = unsafe { P::get_param(&mut self.state, &mut cursor) };
 
         unsafe {
-            bevy_mod_ffi_guest_sys::system::state::bevy_dyn_system_params_drop(
-                params_ptr as *mut dyn_system_param,
+            bevy_mod_ffi_guest_sys::system::state::bevy_system_state_params_drop(
+                params_ptr,
+                params_len as usize,
             )
         };
 
@@ -77,7 +78,8 @@ impl<P: SystemParam + 'static> SystemState<P> {
         Out: Pod,
     {
         let mut system = system.into_system();
-        let state_ptr = self.ptr;
+        // The host system takes ownership of the state
+        let state_ptr = mem::replace(&mut self.ptr, ptr::null_mut());
 
         let output_size = mem::size_of::<Out>();
 
@@ -105,6 +107,7 @@ impl<P: SystemParam + 'static> SystemState<P> {
                 state_ptr,
                 Box::into_raw(Box::new(system_boxed)) as _,
                 bevy_guest_run_system,
+                bevy_guest_drop_system,
                 &mut ptr,
             )
         }
@@ -118,7 +121,9 @@ impl<P: SystemParam + 'static> SystemState<P> {
 
 impl<P: SystemParam> Drop for SystemState<P> {
     fn drop(&mut self) {
-        unsafe { bevy_mod_ffi_guest_sys::system::state::bevy_system_state_drop(self.ptr) };
+        if !self.ptr.is_null() {
+            unsafe { bevy_mod_ffi_guest_sys::system::state::bevy_system_state_drop(self.ptr) };
+        }
     }
 }
 
@@ -128,3 +133,9 @@ pub struct SystemRef<F> {
 }
 
 impl<F> SystemRef<F> {}
+
+impl<F> Drop for SystemRef<F> {
+    fn drop(&mut self) {
+        unsafe { bevy_mod_ffi_guest_sys::system::state::bevy_system_drop(self.ptr) };
+    }
+}
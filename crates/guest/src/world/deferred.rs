@@ -1,12 +1,23 @@
 use crate::{
+    component::{Mutable, SharedComponent},
     query::{Query, QueryData, QueryFilter, QueryState},
-    system::{ParamBuilder, ParamCursor, SystemParam},
-    world::World,
+    system::{Commands, ParamBuilder, ParamCursor, SharedEvent, SystemParam},
+    world::{EntityMut, EntityRef, World},
 };
-use bevy_ecs::entity::Entity;
-use bevy_mod_ffi_core::{deferred_world, query};
+use bevy_ecs::{
+    component::ComponentId,
+    entity::Entity,
+    ptr::{Ptr, PtrMut},
+};
+use bevy_mod_ffi_core::{commands, deferred_world, query};
 use bevy_mod_ffi_guest_sys;
-use std::{marker::PhantomData, ptr};
+use bevy_reflect::TypePath;
+use bytemuck::{Pod, Zeroable};
+use std::{
+    ffi::CString,
+    marker::PhantomData,
+    ptr::{self, NonNull},
+};
 
 pub struct DeferredWorld<'w> {
     ptr: *mut deferred_world,
@@ -43,45 +54,207 @@ impl<'w> DeferredWorld<'w> {
         Query::new(query_ptr, &mut state.state)
     }
 
-    pub fn get_mut<T: bytemuck::Pod>(
+    /// Returns a new [`DeferredWorld`] borrowing from this one.
+    pub fn reborrow(&mut self) -> DeferredWorld<'_> {
+        let ptr = unsafe {
+            bevy_mod_ffi_guest_sys::world::deferred::bevy_deferred_world_reborrow(self.ptr)
+        };
+        unsafe { DeferredWorld::from_ptr(ptr) }
+    }
+
+    pub fn get_component_id<T: TypePath>(&self) -> Option<ComponentId> {
+        let type_path_cstring = CString::new(T::type_path()).unwrap();
+        let type_path_bytes = type_path_cstring.as_bytes_with_nul();
+
+        let mut id: usize = 0;
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::world::deferred::bevy_deferred_world_get_component_id(
+                self.ptr,
+                type_path_bytes.as_ptr(),
+                type_path_bytes.len(),
+                &mut id,
+            )
+        };
+
+        success.then(|| ComponentId::new(id))
+    }
+
+    pub fn get_resource_id<R: TypePath>(&self) -> Option<ComponentId> {
+        let type_path_cstring = CString::new(R::type_path()).unwrap();
+        let type_path_bytes = type_path_cstring.as_bytes_with_nul();
+
+        let mut id: usize = 0;
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::world::deferred::bevy_deferred_world_get_resource_id(
+                self.ptr,
+                type_path_bytes.as_ptr(),
+                type_path_bytes.len(),
+                &mut id,
+            )
+        };
+
+        success.then(|| ComponentId::new(id))
+    }
+
+    pub fn contains_entity(&self, entity: Entity) -> bool {
+        unsafe {
+            bevy_mod_ffi_guest_sys::world::deferred::bevy_deferred_world_contains_entity(
+                self.ptr,
+                entity.to_bits(),
+            )
+        }
+    }
+
+    /// Returns an [`EntityRef`] for `entity`, panicking if it does not exist.
+    pub fn entity(&self, entity: Entity) -> EntityRef<'_> {
+        assert!(
+            self.contains_entity(entity),
+            "Entity {entity:?} does not exist"
+        );
+        EntityRef::new(entity, self)
+    }
+
+    /// Returns an [`EntityMut`] for `entity`, panicking if it does not exist.
+    pub fn entity_mut(&mut self, entity: Entity) -> EntityMut<'_> {
+        assert!(
+            self.contains_entity(entity),
+            "Entity {entity:?} does not exist"
+        );
+        EntityMut::new(entity, self.reborrow())
+    }
+
+    pub fn get<T: SharedComponent>(&self, entity: Entity) -> Option<&T> {
+        let component_id = self.get_component_id::<T>()?;
+        let ptr = self.get_by_id(entity, component_id)?;
+        Some(unsafe { ptr.deref() })
+    }
+
+    pub fn get_by_id(&self, entity: Entity, component_id: ComponentId) -> Option<Ptr<'_>> {
+        let mut out_ptr: *const u8 = ptr::null();
+
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::world::deferred::bevy_deferred_world_get(
+                self.ptr,
+                entity.to_bits(),
+                component_id.index(),
+                &mut out_ptr,
+            )
+        };
+        if !success {
+            return None;
+        }
+
+        let ptr = NonNull::new(out_ptr as *mut u8)?;
+        Some(unsafe { Ptr::new(ptr) })
+    }
+
+    pub fn get_mut<T: SharedComponent<Mutability = Mutable>>(
         &mut self,
         entity: Entity,
-        component_id: usize,
     ) -> Option<&mut T> {
-        let mut ptr: *mut u8 = ptr::null_mut();
+        let component_id = self.get_component_id::<T>()?;
+        let ptr = self.get_mut_by_id(entity, component_id)?;
+        Some(unsafe { ptr.deref_mut() })
+    }
+
+    pub fn get_mut_by_id(
+        &mut self,
+        entity: Entity,
+        component_id: ComponentId,
+    ) -> Option<PtrMut<'_>> {
+        let mut out_ptr: *mut u8 = ptr::null_mut();
 
         let success = unsafe {
             bevy_mod_ffi_guest_sys::world::deferred::bevy_deferred_world_get_mut(
                 self.ptr,
                 entity.to_bits(),
-                component_id,
-                &mut ptr,
+                component_id.index(),
+                &mut out_ptr,
             )
         };
+        if !success {
+            return None;
+        }
+
+        let ptr = NonNull::new(out_ptr)?;
+        Some(unsafe { PtrMut::new(ptr) })
+    }
+
+    /// Returns the resource `R`, panicking if it does not exist.
+    pub fn resource<R: TypePath + Pod + Zeroable>(&self) -> &R {
+        self.get_resource::<R>()
+            .unwrap_or_else(|| panic!("Resource not found: {}", R::type_path()))
+    }
 
-        if !success || ptr.is_null() {
+    pub fn get_resource<R: TypePath + Pod + Zeroable>(&self) -> Option<&R> {
+        let id = self.get_resource_id::<R>()?;
+        let ptr = self.get_resource_by_id(id)?;
+        Some(unsafe { ptr.deref() })
+    }
+
+    pub fn get_resource_by_id(&self, component_id: ComponentId) -> Option<Ptr<'_>> {
+        let mut out_ptr: *const u8 = ptr::null();
+
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::world::deferred::bevy_deferred_world_get_resource(
+                self.ptr,
+                component_id.index(),
+                &mut out_ptr,
+            )
+        };
+        if !success {
             return None;
         }
 
-        Some(unsafe { &mut *(ptr as *mut T) })
+        let ptr = NonNull::new(out_ptr as *mut u8)?;
+        Some(unsafe { Ptr::new(ptr) })
+    }
+
+    /// Returns the resource `R` mutably, panicking if it does not exist.
+    pub fn resource_mut<R: TypePath + Pod + Zeroable>(&mut self) -> &mut R {
+        self.get_resource_mut::<R>()
+            .unwrap_or_else(|| panic!("Resource not found: {}", R::type_path()))
     }
 
-    pub fn get_resource_mut<T: bytemuck::Pod>(&mut self, component_id: usize) -> Option<&mut T> {
-        let mut ptr: *mut u8 = ptr::null_mut();
+    pub fn get_resource_mut<R: TypePath + Pod + Zeroable>(&mut self) -> Option<&mut R> {
+        let id = self.get_resource_id::<R>()?;
+        let ptr = self.get_resource_mut_by_id(id)?;
+        Some(unsafe { ptr.deref_mut() })
+    }
+
+    pub fn get_resource_mut_by_id(&mut self, component_id: ComponentId) -> Option<PtrMut<'_>> {
+        let mut out_ptr: *mut u8 = ptr::null_mut();
 
         let success = unsafe {
             bevy_mod_ffi_guest_sys::world::deferred::bevy_deferred_world_get_resource_mut(
                 self.ptr,
-                component_id,
-                &mut ptr,
+                component_id.index(),
+                &mut out_ptr,
             )
         };
-
-        if !success || ptr.is_null() {
+        if !success {
             return None;
         }
 
-        Some(unsafe { &mut *(ptr as *mut T) })
+        let ptr = NonNull::new(out_ptr)?;
+        Some(unsafe { PtrMut::new(ptr) })
+    }
+
+    /// Returns [`Commands`] that push to the world's command queue.
+    pub fn commands(&mut self) -> Commands<'_, '_> {
+        let mut commands_ptr: *mut commands = ptr::null_mut();
+        unsafe {
+            bevy_mod_ffi_guest_sys::world::deferred::bevy_deferred_world_commands(
+                self.ptr,
+                &mut commands_ptr,
+            );
+            Commands::from_ptr(commands_ptr)
+        }
+    }
+
+    /// Triggers `event` once the world's commands are applied.
+    pub fn trigger<E: SharedEvent + Send + 'static>(&mut self, event: E) {
+        self.commands().trigger(event);
     }
 }
 
@@ -1,16 +1,17 @@
 use crate::{
+    component::{Mutable, SharedComponent},
     system::{
         IntoEntityObserverSystem, OnEntity, ParamBuilder, ParamCursor, SharedEvent, System,
         SystemParam,
     },
-    world::World,
+    world::{Bundle, BundleWriter, DeferredWorld, World, bundle_components},
 };
 use bevy_ecs::{
     component::ComponentId,
     entity::Entity,
     ptr::{Ptr, PtrMut},
 };
-use bevy_mod_ffi_core::{entity_world_mut, filtered_entity_mut};
+use bevy_mod_ffi_core::{entity_world_mut, filtered_entity_mut, world};
 use bevy_mod_ffi_guest_sys::{self, system::ObserverClosure};
 use std::{ffi::CString, marker::PhantomData, ptr::NonNull};
 
@@ -33,6 +34,71 @@ impl<'w> EntityWorldMut<'w> {
         self.id
     }
 
+    pub fn insert<B: Bundle>(self, bundle: B) -> Self {
+        let mut writer = BundleWriter::default();
+        let components = bundle_components(self.world, bundle, &mut writer);
+
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::world::entity::bevy_entity_world_mut_insert(
+                self.ptr,
+                components.as_ptr(),
+                components.len(),
+            )
+        };
+        assert!(success, "Failed to insert bundle for entity {:?}", self.id);
+
+        self
+    }
+
+    pub fn remove<C: SharedComponent>(self) -> Self {
+        if let Some(component_id) = self.world.get_component_id::<C>() {
+            unsafe {
+                bevy_mod_ffi_guest_sys::world::entity::bevy_entity_world_mut_remove(
+                    self.ptr,
+                    component_id.index(),
+                );
+            }
+        }
+
+        self
+    }
+
+    /// Runs `f` with mutable access to the world, updating this entity afterwards.
+    pub fn world_scope<U, F>(&mut self, f: F) -> U
+    where
+        F: FnOnce(&mut World) -> U,
+    {
+        struct Scope<F, U> {
+            f: Option<F>,
+            out: Option<U>,
+        }
+
+        unsafe extern "C" fn run_scope<U, F: FnOnce(&mut World) -> U>(
+            scope_ptr: *mut (),
+            world_ptr: *mut world,
+        ) {
+            let scope = unsafe { &mut *(scope_ptr as *mut Scope<F, U>) };
+            let mut world = unsafe { World::from_ptr(world_ptr) };
+            let f = scope.f.take().unwrap();
+            scope.out = Some(f(&mut world));
+        }
+
+        let mut scope = Scope {
+            f: Some(f),
+            out: None,
+        };
+
+        unsafe {
+            bevy_mod_ffi_guest_sys::world::entity::bevy_entity_world_mut_world_scope(
+                self.ptr,
+                &mut scope as *mut Scope<F, U> as *mut (),
+                run_scope::<U, F>,
+            );
+        }
+
+        scope.out.unwrap()
+    }
+
     pub fn observe<E, Marker, S>(self, observer: S) -> Self
     where
         E: SharedEvent + 'static,
@@ -78,6 +144,7 @@ impl<'w> EntityWorldMut<'w> {
                 event_name_bytes.len(),
                 Box::into_raw(Box::new(observer_boxed)) as _,
                 bevy_mod_ffi_guest_sys::system::bevy_guest_run_observer,
+                bevy_mod_ffi_guest_sys::system::bevy_guest_drop_observer,
             )
         };
 
@@ -183,3 +250,75 @@ impl Drop for FilteredEntityMut<'_> {
         unsafe { bevy_mod_ffi_guest_sys::world::entity::bevy_filtered_entity_mut_drop(self.ptr) };
     }
 }
+
+/// A read-only view of an entity in a [`DeferredWorld`].
+pub struct EntityRef<'w> {
+    id: Entity,
+    world: &'w DeferredWorld<'w>,
+}
+
+impl<'w> EntityRef<'w> {
+    pub(crate) fn new(id: Entity, world: &'w DeferredWorld<'w>) -> Self {
+        Self { id, world }
+    }
+
+    pub fn id(&self) -> Entity {
+        self.id
+    }
+
+    pub fn contains<T: SharedComponent>(&self) -> bool {
+        self.get::<T>().is_some()
+    }
+
+    pub fn get<T: SharedComponent>(&self) -> Option<&'w T> {
+        self.world.get(self.id)
+    }
+}
+
+/// A mutable view of an entity in a [`DeferredWorld`], which cannot change its archetype.
+pub struct EntityMut<'w> {
+    id: Entity,
+    world: DeferredWorld<'w>,
+}
+
+impl<'w> EntityMut<'w> {
+    pub(crate) fn new(id: Entity, world: DeferredWorld<'w>) -> Self {
+        Self { id, world }
+    }
+
+    pub fn id(&self) -> Entity {
+        self.id
+    }
+
+    pub fn contains<T: SharedComponent>(&self) -> bool {
+        self.get::<T>().is_some()
+    }
+
+    pub fn get<T: SharedComponent>(&self) -> Option<&T> {
+        self.world.get(self.id)
+    }
+
+    pub fn get_mut<T: SharedComponent<Mutability = Mutable>>(&mut self) -> Option<&mut T> {
+        self.world.get_mut(self.id)
+    }
+}
+
+/// The entities of a [`World`].
+pub struct Entities<'w> {
+    world: &'w World,
+}
+
+impl<'w> Entities<'w> {
+    pub(crate) fn new(world: &'w World) -> Self {
+        Self { world }
+    }
+
+    /// Returns the number of entities in the world.
+    pub fn len(&self) -> u32 {
+        unsafe { bevy_mod_ffi_guest_sys::world::bevy_world_entities_len(self.world.ptr) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
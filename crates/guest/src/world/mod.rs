@@ -1,18 +1,27 @@
 use crate::{
-    component::{HookContext, SharedComponent, StorageType},
+    component::{
+        CallerLocation, ComponentCloneBehavior, ComponentHooks, ComponentMutability, HookContext,
+        Mutable, RelationshipHookMode, RequiredComponentsRegistrator, SharedComponent, StorageType,
+        clone_wrapper, drop_wrapper, map_entities_wrapper,
+    },
     query::{QueryData, QueryFilter, QueryState},
     system::{
         IntoObserverSystem, IntoSystem, On, ParamBuilder, ParamCursor, SharedEvent, System,
         SystemParam, SystemRef, SystemState,
     },
 };
-use bevy_mod_ffi_core::{BundleComponent, ComponentHookFn, deferred_world, world};
+use bevy_mod_ffi_core::{
+    BundleComponent, ComponentCloneFn, ComponentDropFn, ComponentHookContext, ComponentHookFn,
+    MapEntitiesFn, RegisterComponentResult, ResourceScopeResult, deferred_world, world,
+};
 use bevy_mod_ffi_guest_sys::system::ObserverClosure;
 use bevy_reflect::TypePath;
 use std::{
     alloc::Layout,
     ffi::CString,
+    mem,
     ptr::{self, NonNull},
+    slice, str,
 };
 
 pub use bevy_ecs::{
@@ -23,7 +32,7 @@ pub use bevy_ecs::{
 pub use bytemuck::{Pod, Zeroable};
 
 mod entity;
-pub use entity::{EntityWorldMut, FilteredEntityMut};
+pub use entity::{Entities, EntityMut, EntityRef, EntityWorldMut, FilteredEntityMut};
 
 mod deferred;
 pub use deferred::DeferredWorld;
@@ -32,22 +41,45 @@ macro_rules! make_hook_wrapper {
     ($name:ident, $hook_getter:expr) => {
         unsafe extern "C" fn $name<C: SharedComponent>(
             deferred_ptr: *mut deferred_world,
-            entity_bits: u64,
-            component_id: usize,
+            context: *const ComponentHookContext,
         ) {
             if let Some(hook) = $hook_getter {
                 let deferred = unsafe { DeferredWorld::from_ptr(deferred_ptr) };
-                let context = HookContext {
-                    entity: Entity::from_bits(entity_bits),
-                    component_id: ComponentId::new(component_id),
-                    caller: None,
-                };
+                let context = unsafe { hook_context(&*context) };
                 hook(deferred, context);
             }
         }
     };
 }
 
+pub(crate) unsafe fn hook_context(context: &ComponentHookContext) -> HookContext {
+    let caller = (!context.caller_file_ptr.is_null()).then(|| {
+        // The file name is static data in the host
+        let file = unsafe {
+            str::from_utf8_unchecked(slice::from_raw_parts(
+                context.caller_file_ptr,
+                context.caller_file_len,
+            ))
+        };
+        CallerLocation {
+            file,
+            line: context.caller_line,
+            column: context.caller_column,
+        }
+    });
+
+    HookContext {
+        entity: Entity::from_bits(context.entity),
+        component_id: ComponentId::new(context.component_id),
+        caller,
+        relationship_hook_mode: match context.relationship_hook_mode {
+            0 => RelationshipHookMode::Run,
+            1 => RelationshipHookMode::RunIfNotLinked,
+            _ => RelationshipHookMode::Skip,
+        },
+    }
+}
+
 make_hook_wrapper!(on_add_wrapper, C::on_add());
 make_hook_wrapper!(on_insert_wrapper, C::on_insert());
 make_hook_wrapper!(on_replace_wrapper, C::on_replace());
@@ -58,6 +90,17 @@ pub struct World {
     pub(crate) ptr: *mut world,
 }
 
+/// Creates an instance of a type from the guest [`World`].
+pub trait FromWorld {
+    fn from_world(world: &mut World) -> Self;
+}
+
+impl<T: Default> FromWorld for T {
+    fn from_world(_world: &mut World) -> Self {
+        T::default()
+    }
+}
+
 impl World {
     #[doc(hidden)]
     pub unsafe fn from_ptr(ptr: *mut world) -> Self {
@@ -82,8 +125,21 @@ impl World {
         let on_despawn: Option<ComponentHookFn> =
             C::on_despawn().map(|_| on_despawn_wrapper::<C> as ComponentHookFn);
 
+        let drop = mem::needs_drop::<C>().then_some(drop_wrapper::<C> as ComponentDropFn);
+
+        let (clone_ignore, clone_fn, clone_data) = match C::clone_behavior() {
+            // Components with drop glue can't be copied byte for byte
+            ComponentCloneBehavior::Default => (drop.is_some(), None, ptr::null()),
+            ComponentCloneBehavior::Ignore => (true, None, ptr::null()),
+            ComponentCloneBehavior::Custom(f) => (
+                false,
+                Some(clone_wrapper::<C> as ComponentCloneFn),
+                f as *const (),
+            ),
+        };
+
         let mut id: usize = 0;
-        let success = unsafe {
+        let result = unsafe {
             bevy_mod_ffi_guest_sys::world::bevy_world_register_component(
                 self.ptr,
                 name_bytes.as_ptr(),
@@ -91,18 +147,38 @@ impl World {
                 layout.size(),
                 layout.align(),
                 matches!(C::STORAGE_TYPE, StorageType::Table) as u8,
+                C::Mutability::MUTABLE,
+                drop,
                 on_add,
                 on_insert,
                 on_replace,
                 on_remove,
                 on_despawn,
+                clone_ignore,
+                clone_fn,
+                clone_data,
+                Some(map_entities_wrapper::<C> as MapEntitiesFn),
                 &mut id,
             )
         };
 
-        assert!(success, "Failed to register component: {}", name);
+        match result {
+            RegisterComponentResult::Registered => {}
+            RegisterComponentResult::InvalidLayout => {
+                panic!(
+                    "Failed to register component with an invalid layout: {}",
+                    name
+                )
+            }
+            RegisterComponentResult::NoDropSlots => panic!(
+                "Failed to register component, since every host drop function is in use: {}",
+                name
+            ),
+        }
 
-        ComponentId::new(id)
+        let id = ComponentId::new(id);
+        C::register_required_components(id, &mut RequiredComponentsRegistrator::new(self, id));
+        id
     }
 
     pub fn get_resource_id<R>(&self) -> Option<ComponentId>
@@ -160,6 +236,116 @@ impl World {
         Some(unsafe { Ptr::new(ptr) })
     }
 
+    /// Returns the resource `R`, panicking if it does not exist.
+    pub fn resource<R>(&self) -> &R
+    where
+        R: TypePath + Pod + Zeroable,
+    {
+        self.get_resource::<R>()
+            .unwrap_or_else(|| panic!("Resource not found: {}", R::type_path()))
+    }
+
+    /// Returns the resource `R` mutably, panicking if it does not exist.
+    pub fn resource_mut<R>(&mut self) -> &mut R
+    where
+        R: TypePath + Pod + Zeroable,
+    {
+        self.get_resource_mut::<R>()
+            .unwrap_or_else(|| panic!("Resource not found: {}", R::type_path()))
+    }
+
+    pub fn get_resource_mut<R>(&mut self) -> Option<&mut R>
+    where
+        R: TypePath + Pod + Zeroable,
+    {
+        let id = self.get_resource_id::<R>()?;
+        let ptr = self.get_resource_mut_by_id(id)?;
+        Some(unsafe { ptr.deref_mut() })
+    }
+
+    pub fn get_resource_mut_by_id(&mut self, id: ComponentId) -> Option<PtrMut<'_>> {
+        let mut out_ptr: *mut u8 = std::ptr::null_mut();
+
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::world::bevy_world_get_resource_mut(
+                self.ptr,
+                id.index(),
+                &mut out_ptr,
+            )
+        };
+        if !success {
+            return None;
+        }
+
+        let ptr = NonNull::new(out_ptr)?;
+        Some(unsafe { PtrMut::new(ptr) })
+    }
+
+    pub fn contains_resource<R: TypePath>(&self) -> bool {
+        self.get_resource_id::<R>().is_some_and(|id| unsafe {
+            bevy_mod_ffi_guest_sys::world::bevy_world_contains_resource(self.ptr, id.index())
+        })
+    }
+
+    /// Temporarily removes the resource `R` to run `f` with mutable access to both it and the world.
+    pub fn resource_scope<R, U, F>(&mut self, f: F) -> U
+    where
+        R: TypePath + Pod + Zeroable,
+        F: FnOnce(&mut World, &mut R) -> U,
+    {
+        struct Scope<F, U> {
+            f: Option<F>,
+            out: Option<U>,
+        }
+
+        unsafe extern "C" fn run_scope<R, U, F: FnOnce(&mut World, &mut R) -> U>(
+            scope_ptr: *mut (),
+            world_ptr: *mut world,
+            resource_ptr: *mut u8,
+        ) {
+            let scope = unsafe { &mut *(scope_ptr as *mut Scope<F, U>) };
+            let mut world = unsafe { World::from_ptr(world_ptr) };
+            let resource = unsafe { &mut *(resource_ptr as *mut R) };
+            let f = scope.f.take().unwrap();
+            scope.out = Some(f(&mut world, resource));
+        }
+
+        let id = self
+            .get_resource_id::<R>()
+            .unwrap_or_else(|| panic!("Resource not found: {}", R::type_path()));
+        let mut scope = Scope {
+            f: Some(f),
+            out: None,
+        };
+
+        let result = unsafe {
+            bevy_mod_ffi_guest_sys::world::bevy_world_resource_scope(
+                self.ptr,
+                id.index(),
+                &mut scope as *mut Scope<F, U> as *mut (),
+                run_scope::<R, U, F>,
+            )
+        };
+        match result {
+            ResourceScopeResult::Ran => {}
+            ResourceScopeResult::NotFound => panic!("Resource not found: {}", R::type_path()),
+            ResourceScopeResult::NeedsDrop => panic!(
+                "Resource needs dropping, so it can't be moved out of the world: {}",
+                R::type_path()
+            ),
+        }
+
+        scope.out.unwrap()
+    }
+
+    /// Returns the hooks of `C`, registering it if needed.
+    pub fn component_hooks<C: SharedComponent>(&mut self) -> ComponentHooks<'_> {
+        let component_id = self
+            .get_component_id::<C>()
+            .unwrap_or_else(|| self.register_component::<C>());
+        ComponentHooks::new(self, component_id)
+    }
+
     pub fn get_component_id<R>(&self) -> Option<ComponentId>
     where
         R: TypePath,
@@ -196,7 +382,9 @@ impl World {
         QueryState::new(self)
     }
 
-    pub fn run_system<Marker, In, Out, S>(&mut self, input: In, system: S) -> Out
+    /// Runs `system` once with `input`, returning `None` if it was skipped
+    /// because its params failed validation, like a [`Single`](crate::query::Single) without a match.
+    pub fn run_system<Marker, In, Out, S>(&mut self, input: In, system: S) -> Option<Out>
     where
         S: IntoSystem<Marker, In = In, Out = Out>,
         S::System: 'static,
@@ -204,11 +392,18 @@ impl World {
         In: Pod,
         Out: Pod,
     {
-        let r = SystemState::<<S::System as System>::Param>::new(self).build(system);
-        self.run_system_ref(input, r)
+        let mut r = SystemState::<<S::System as System>::Param>::new(self).build(system);
+        self.run_system_ref(input, &mut r)
     }
 
-    pub fn run_system_ref<In, Out, S>(&mut self, input: In, system: SystemRef<S>) -> Out
+    /// Runs a built system once with `input`, returning `None` if it was skipped.
+    ///
+    /// The system keeps its state, so it can be run again.
+    pub fn run_system_ref<In, Out, S>(
+        &mut self,
+        input: In,
+        system: &mut SystemRef<S>,
+    ) -> Option<Out>
     where
         In: Pod,
         Out: Pod,
@@ -216,7 +411,7 @@ impl World {
         let input_bytes = bytemuck::bytes_of(&input);
         let mut output = bytemuck::zeroed_box::<Out>();
 
-        unsafe {
+        let ran = unsafe {
             bevy_mod_ffi_guest_sys::world::bevy_world_run_system(
                 self.ptr,
                 system.ptr as *mut _,
@@ -225,13 +420,12 @@ impl World {
             )
         };
 
-        *output
+        ran.then_some(*output)
     }
 
     pub fn spawn<B: Bundle>(&mut self, bundle: B) -> EntityWorldMut<'_> {
-        let mut components = Vec::new();
-        let mut storage = Vec::new();
-        bundle.bundle(self, &mut components, &mut storage);
+        let mut writer = BundleWriter::default();
+        let components = bundle_components(self, bundle, &mut writer);
 
         let mut entity_bits: u64 = 0;
         let mut entity_ptr = ptr::null_mut();
@@ -250,7 +444,52 @@ impl World {
         unsafe { EntityWorldMut::from_ptr(entity, entity_ptr, self) }
     }
 
+    /// Spawns an entity for each bundle of `iter`.
+    pub fn spawn_batch<I>(&mut self, iter: I) -> Vec<Entity>
+    where
+        I: IntoIterator,
+        I::Item: Bundle,
+    {
+        let mut writer = BundleWriter::default();
+        let mut count = 0;
+        for bundle in iter {
+            bundle.write_components(&mut writer);
+            count += 1;
+        }
+
+        if count == 0 {
+            return Vec::new();
+        }
+
+        // Every bundle has the same components, so their ids are only looked up once
+        let mut ids = Vec::new();
+        I::Item::component_ids(self, &mut ids);
+        let component_ids: Vec<usize> = ids.iter().map(|id| id.index()).collect();
+        let data = writer.data;
+
+        let mut entity_bits = vec![0u64; count];
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::world::bevy_world_spawn_batch(
+                self.ptr,
+                component_ids.as_ptr(),
+                component_ids.len(),
+                data.as_ptr(),
+                data.len(),
+                count,
+                entity_bits.as_mut_ptr(),
+            )
+        };
+        assert!(success, "Failed to spawn batch");
+
+        entity_bits.into_iter().map(Entity::from_bits).collect()
+    }
+
     pub fn entity_mut(&mut self, entity: Entity) -> EntityWorldMut<'_> {
+        self.get_entity_mut(entity)
+            .unwrap_or_else(|| panic!("Failed to get entity {:?}", entity))
+    }
+
+    pub fn get_entity_mut(&mut self, entity: Entity) -> Option<EntityWorldMut<'_>> {
         let mut entity_ptr = ptr::null_mut();
         let success = unsafe {
             bevy_mod_ffi_guest_sys::world::bevy_world_entity_mut(
@@ -259,9 +498,95 @@ impl World {
                 &mut entity_ptr,
             )
         };
-        assert!(success, "Failed to get entity {:?}", entity);
+        if !success {
+            return None;
+        }
 
-        unsafe { EntityWorldMut::from_ptr(entity, entity_ptr, self) }
+        Some(unsafe { EntityWorldMut::from_ptr(entity, entity_ptr, self) })
+    }
+
+    pub fn get<C: SharedComponent>(&self, entity: Entity) -> Option<&C> {
+        let component_id = self.get_component_id::<C>()?;
+        let ptr = self.get_by_id(entity, component_id)?;
+        Some(unsafe { ptr.deref() })
+    }
+
+    pub fn get_by_id(&self, entity: Entity, component_id: ComponentId) -> Option<Ptr<'_>> {
+        let mut out_ptr: *const u8 = ptr::null();
+
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::world::bevy_world_get(
+                self.ptr,
+                entity.to_bits(),
+                component_id.index(),
+                &mut out_ptr,
+            )
+        };
+        if !success {
+            return None;
+        }
+
+        let ptr = NonNull::new(out_ptr as *mut u8)?;
+        Some(unsafe { Ptr::new(ptr) })
+    }
+
+    pub fn get_mut<C: SharedComponent<Mutability = Mutable>>(
+        &mut self,
+        entity: Entity,
+    ) -> Option<&mut C> {
+        let component_id = self.get_component_id::<C>()?;
+        let ptr = self.get_mut_by_id(entity, component_id)?;
+        Some(unsafe { ptr.deref_mut() })
+    }
+
+    pub fn get_mut_by_id(
+        &mut self,
+        entity: Entity,
+        component_id: ComponentId,
+    ) -> Option<PtrMut<'_>> {
+        let mut out_ptr: *mut u8 = ptr::null_mut();
+
+        let success = unsafe {
+            bevy_mod_ffi_guest_sys::world::bevy_world_get_mut(
+                self.ptr,
+                entity.to_bits(),
+                component_id.index(),
+                &mut out_ptr,
+            )
+        };
+        if !success {
+            return None;
+        }
+
+        let ptr = NonNull::new(out_ptr)?;
+        Some(unsafe { PtrMut::new(ptr) })
+    }
+
+    /// Despawns `entity`, returning `false` if it does not exist.
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        unsafe { bevy_mod_ffi_guest_sys::world::bevy_world_despawn(self.ptr, entity.to_bits()) }
+    }
+
+    pub fn entities(&self) -> Entities<'_> {
+        Entities::new(self)
+    }
+
+    /// Returns the entities in the world, including those without shared components.
+    pub fn iter_entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        let capacity = self.entities().len() as usize;
+        let mut entities = vec![0u64; capacity];
+        let mut len = 0;
+        unsafe {
+            bevy_mod_ffi_guest_sys::world::bevy_world_iter_entities(
+                self.ptr,
+                entities.as_mut_ptr(),
+                capacity,
+                &mut len,
+            );
+        }
+        entities.truncate(len);
+
+        entities.into_iter().map(Entity::from_bits)
     }
 
     pub fn add_observer<E, Marker, S>(&mut self, observer: S)
@@ -301,6 +626,7 @@ impl World {
                 event_name_bytes.len(),
                 Box::into_raw(Box::new(observer_boxed)) as _,
                 bevy_mod_ffi_guest_sys::system::bevy_guest_run_observer,
+                bevy_mod_ffi_guest_sys::system::bevy_guest_drop_observer,
             )
         };
 
@@ -351,41 +677,86 @@ impl World {
     }
 }
 
-pub trait Bundle {
-    fn bundle(
-        self,
-        world: &mut World,
-        components: &mut Vec<BundleComponent>,
-        storage: &mut Vec<Box<[u8]>>,
-    );
+/// A set of components that are spawned or inserted together.
+pub trait Bundle: Sized {
+    /// Appends the ids of the bundle's components, in the order they're written.
+    fn component_ids(world: &World, ids: &mut Vec<ComponentId>);
+
+    /// Moves each component of the bundle into `writer`.
+    fn write_components(self, writer: &mut BundleWriter);
 }
 
-impl<C: SharedComponent + Pod> Bundle for C {
-    fn bundle(
-        self,
-        world: &mut World,
-        components: &mut Vec<BundleComponent>,
-        storage: &mut Vec<Box<[u8]>>,
-    ) {
-        let component_id = world.get_component_id::<C>().unwrap();
-        let bytes = bytemuck::bytes_of(&self).to_vec().into_boxed_slice();
-        let ptr = bytes.as_ptr();
-        storage.push(bytes);
-        components.push(BundleComponent {
-            component_id: component_id.index(),
-            ptr,
-        });
+/// The components of one or more bundles, moved back to back into one buffer for the host.
+#[derive(Default)]
+pub struct BundleWriter {
+    data: Vec<u8>,
+    offsets: Vec<usize>,
+}
+
+impl BundleWriter {
+    /// Moves `component` to the end of the buffer.
+    pub fn write<C: SharedComponent>(&mut self, component: C) {
+        let offset = self.data.len();
+        self.data.resize(offset + mem::size_of::<C>(), 0);
+        // Ownership of the component moves to the host when its bytes are copied
+        unsafe {
+            self.data
+                .as_mut_ptr()
+                .add(offset)
+                .cast::<C>()
+                .write_unaligned(component)
+        };
+        self.offsets.push(offset);
+    }
+
+    /// Returns the components written for `ids`, pointing into the buffer.
+    fn components(&self, ids: &[ComponentId]) -> Vec<BundleComponent> {
+        ids.iter()
+            .zip(&self.offsets)
+            .map(|(id, offset)| BundleComponent {
+                component_id: id.index(),
+                ptr: unsafe { self.data.as_ptr().add(*offset) },
+            })
+            .collect()
+    }
+}
+
+/// Moves `bundle` into `writer`, returning its components.
+pub(crate) fn bundle_components<B: Bundle>(
+    world: &World,
+    bundle: B,
+    writer: &mut BundleWriter,
+) -> Vec<BundleComponent> {
+    let mut ids = Vec::new();
+    B::component_ids(world, &mut ids);
+    bundle.write_components(writer);
+    writer.components(&ids)
+}
+
+impl<C: SharedComponent> Bundle for C {
+    fn component_ids(world: &World, ids: &mut Vec<ComponentId>) {
+        ids.push(world.get_component_id::<C>().unwrap());
+    }
+
+    fn write_components(self, writer: &mut BundleWriter) {
+        writer.write(self);
     }
 }
 
 macro_rules! impl_bundle_tuple {
     ($($item:ident),+) => {
         impl<$($item: Bundle),+> Bundle for ($($item,)+) {
-            fn bundle(self, world: &mut World, components: &mut Vec<BundleComponent>, storage: &mut Vec<Box<[u8]>>) {
+            fn component_ids(world: &World, ids: &mut Vec<ComponentId>) {
+                $(
+                    $item::component_ids(world, ids);
+                )+
+            }
+
+            fn write_components(self, writer: &mut BundleWriter) {
                 #[allow(non_snake_case)]
                 let ($($item,)+) = self;
                 $(
-                    $item.bundle(world, components, storage);
+                    $item.write_components(writer);
                 )+
             }
         }
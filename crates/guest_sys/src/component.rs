@@ -0,0 +1,11 @@
+use bevy_mod_ffi_core::*;
+
+unsafe extern "C" {
+    pub fn bevy_entity_mapper_get_mapped(mapper: *mut entity_mapper, source_bits: u64) -> u64;
+
+    pub fn bevy_entity_mapper_set_mapped(
+        mapper: *mut entity_mapper,
+        source_bits: u64,
+        target_bits: u64,
+    );
+}
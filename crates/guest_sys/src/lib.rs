@@ -1,3 +1,4 @@
+pub mod component;
 pub mod query;
 pub mod system;
 pub mod world;
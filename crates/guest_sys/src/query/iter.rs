@@ -7,5 +7,15 @@ unsafe extern "C" {
         out_entity: *mut *mut filtered_entity_mut,
     ) -> bool;
 
+    pub fn bevy_query_iter_next_batch(
+        iter: *mut query_iter,
+        fetches_ptr: *const FetchComponent,
+        fetches_len: usize,
+        out_entities: *mut u64,
+        out_components: *mut *mut u8,
+        capacity: usize,
+        out_len: *mut usize,
+    ) -> bool;
+
     pub fn bevy_query_iter_drop(iter: *mut query_iter);
 }
@@ -0,0 +1,22 @@
+use bevy_mod_ffi_core::*;
+
+unsafe extern "C" {
+    pub fn bevy_query_transmute_lens(
+        query: *mut query,
+        filter_ptr: *const QueryTerm,
+        filter_len: usize,
+        out_lens: *mut *mut query_lens,
+    ) -> bool;
+
+    pub fn bevy_query_join(
+        query: *mut query,
+        other: *mut query,
+        filter_ptr: *const QueryTerm,
+        filter_len: usize,
+        out_lens: *mut *mut query_lens,
+    ) -> bool;
+
+    pub fn bevy_query_lens_query(lens: *mut query_lens, out_query: *mut *mut query) -> bool;
+
+    pub fn bevy_query_lens_drop(lens: *mut query_lens);
+}
@@ -1,21 +1,95 @@
 use bevy_mod_ffi_core::*;
+use std::{cmp::Ordering, slice};
 
 pub mod builder;
 pub mod iter;
+pub mod lens;
+pub mod par_iter;
 pub mod state;
 
 pub use builder::*;
 pub use iter::*;
+pub use lens::*;
+pub use par_iter::*;
 pub use state::*;
 
 unsafe extern "C" {
     pub fn bevy_query_iter_mut(query: *mut query, out_iter: *mut *mut query_iter) -> bool;
 
+    pub fn bevy_query_iter_mut_sort_by(
+        query: *mut query,
+        component_id: usize,
+        f_ptr: *mut (),
+        compare_fn: CompareFn,
+        out_iter: *mut *mut query_iter,
+    ) -> bool;
+
+    pub fn bevy_query_iter_mut_sort_by_entity(
+        query: *mut query,
+        out_iter: *mut *mut query_iter,
+    ) -> bool;
+
+    pub fn bevy_query_iter_combinations_mut(
+        query: *mut query,
+        k: usize,
+        out_iter: *mut *mut query_combination_iter,
+    ) -> bool;
+
+    pub fn bevy_query_combination_iter_fetch_next(
+        iter: *mut query_combination_iter,
+        fetches_ptr: *const FetchComponent,
+        fetches_len: usize,
+        out_entities: *mut u64,
+        out_entities_len: usize,
+        out_components: *mut *mut u8,
+    ) -> bool;
+
+    pub fn bevy_query_combination_iter_drop(iter: *mut query_combination_iter);
+
     pub fn bevy_query_get_mut(
         query: *mut query,
         entity_id: u64,
         out_entity: *mut *mut filtered_entity_mut,
     ) -> bool;
 
+    pub fn bevy_query_for_each_chunk(
+        query: *mut query,
+        fetches_ptr: *const FetchComponent,
+        fetches_len: usize,
+        f_ptr: *mut (),
+        run_chunk_fn: RunChunkFn,
+    ) -> bool;
+
+    pub fn bevy_query_get_component_id(
+        query: *mut query,
+        type_path_ptr: *const u8,
+        type_path_len: usize,
+        out_id: *mut usize,
+    ) -> bool;
+
     pub fn bevy_query_drop(iter: *mut query);
 }
+
+pub type ChunkClosure<'a> = &'a mut dyn FnMut(&[u64], *const *mut u8);
+
+#[allow(clippy::missing_safety_doc)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_guest_run_chunk(
+    f_ptr: *mut (),
+    entities: *const u64,
+    columns: *const *mut u8,
+    len: usize,
+) {
+    let f = unsafe { &mut *(f_ptr as *mut ChunkClosure) };
+    let entities_slice = unsafe { slice::from_raw_parts(entities, len) };
+    f(entities_slice, columns);
+}
+
+pub type CompareClosure<'a> = &'a mut dyn FnMut(*const u8, *const u8) -> Ordering;
+
+#[allow(clippy::missing_safety_doc)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_guest_run_compare(f_ptr: *mut (), a: *const u8, b: *const u8) -> i8 {
+    let f = unsafe { &mut *(f_ptr as *mut CompareClosure) };
+    f(a, b) as i8
+}
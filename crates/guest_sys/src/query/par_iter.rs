@@ -0,0 +1,28 @@
+use bevy_mod_ffi_core::*;
+use std::slice;
+
+pub type BatchClosure<'a> = &'a (dyn Fn(&[u64], *const *mut u8) + Sync);
+
+unsafe extern "C" {
+    pub fn bevy_query_par_for_each(
+        query: *mut query,
+        fetches_ptr: *const FetchComponent,
+        fetches_len: usize,
+        batch_size: usize,
+        f_ptr: *mut (),
+        run_batch_fn: RunChunkFn,
+    ) -> bool;
+}
+
+#[allow(clippy::missing_safety_doc)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_guest_run_batch(
+    f_ptr: *mut (),
+    entities: *const u64,
+    components: *const *mut u8,
+    len: usize,
+) {
+    let f = unsafe { &*(f_ptr as *const BatchClosure) };
+    let entities_slice = unsafe { slice::from_raw_parts(entities, len) };
+    f(entities_slice, components);
+}
@@ -7,5 +7,7 @@ unsafe extern "C" {
         out_iter: *mut *mut query_iter,
     ) -> bool;
 
+    pub fn bevy_query_state_update_archetypes(world: *mut world, query: *mut query_state) -> bool;
+
     pub fn bevy_query_state_drop(query: *mut query_state);
 }
@@ -11,6 +11,7 @@ unsafe extern "C" {
         event_name_len: usize,
         f_ptr: *mut (),
         run_observer_fn: RunObserverFn,
+        drop_fn: DropClosureFn,
     ) -> bool;
 }
 
@@ -26,3 +27,9 @@ pub unsafe extern "C" fn bevy_guest_run_observer(
     let params_slice = unsafe { slice::from_raw_parts(params, params_len) };
     f(params_slice, trigger_ptr);
 }
+
+#[allow(clippy::missing_safety_doc)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_guest_drop_observer(f_ptr: *mut ()) {
+    let _ = unsafe { Box::from_raw(f_ptr as *mut ObserverClosure) };
+}
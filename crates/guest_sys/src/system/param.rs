@@ -8,10 +8,26 @@ unsafe extern "C" {
         query_ptr: *mut query_builder,
     ) -> bool;
 
+    pub fn bevy_param_builder_add_single(
+        builder: *mut param_builder,
+        query_ptr: *mut query_builder,
+    ) -> bool;
+
+    pub fn bevy_param_builder_add_populated(
+        builder: *mut param_builder,
+        query_ptr: *mut query_builder,
+    ) -> bool;
+
     pub fn bevy_param_builder_add_commands(builder: *mut param_builder) -> bool;
 
     pub fn bevy_param_builder_add_deferred_world(builder: *mut param_builder) -> bool;
 
+    pub fn bevy_param_builder_add_param_set(
+        builder: *mut param_builder,
+        members_ptr: *const *mut param_builder,
+        members_len: usize,
+    ) -> bool;
+
     pub fn bevy_param_builder_build(
         world_ptr: *mut world,
         builder: *mut param_builder,
@@ -25,6 +41,17 @@ unsafe extern "C" {
         out_query: *mut *mut query,
     ) -> bool;
 
+    pub fn bevy_dyn_system_param_downcast_single(
+        param_ptr: *mut dyn_system_param,
+        out_entity: *mut u64,
+        out_entity_mut: *mut *mut filtered_entity_mut,
+    ) -> bool;
+
+    pub fn bevy_dyn_system_param_downcast_populated(
+        param_ptr: *mut dyn_system_param,
+        out_query: *mut *mut query,
+    ) -> bool;
+
     pub fn bevy_dyn_system_param_downcast_commands(
         param_ptr: *mut dyn_system_param,
         out_commands: *mut *mut commands,
@@ -35,6 +62,22 @@ unsafe extern "C" {
         out_deferred: *mut *mut deferred_world,
     ) -> bool;
 
+    pub fn bevy_dyn_system_param_downcast_param_set(
+        param_ptr: *mut dyn_system_param,
+        out_set: *mut *mut param_set,
+    ) -> bool;
+
+    pub fn bevy_param_set_get_mut(
+        set_ptr: *mut param_set,
+        index: usize,
+        out_params: *mut *mut *mut dyn_system_param,
+        out_params_len: *mut usize,
+    ) -> bool;
+
+    pub fn bevy_param_set_params_drop(params_ptr: *mut *mut dyn_system_param, params_len: usize);
+
+    pub fn bevy_param_set_drop(set_ptr: *mut param_set);
+
     pub fn bevy_commands_push(
         commands_ptr: *mut commands,
         world_ptr: *mut world,
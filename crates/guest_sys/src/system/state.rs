@@ -8,15 +8,20 @@ unsafe extern "C" {
         out_params_len: *mut i32,
     );
 
+    pub fn bevy_system_state_params_drop(params: *mut *mut dyn_system_param, params_len: usize);
+
     pub fn bevy_system_state_apply(world: *mut world, state: *mut system_state);
 
     pub fn bevy_system_state_build(
         state: *mut system_state,
         f_ptr: *mut (),
         run_system_fn: RunSystemFn,
+        drop_fn: DropClosureFn,
         out_ptr: *mut *mut system,
     );
 
+    pub fn bevy_system_drop(system: *mut system);
+
     pub fn bevy_system_state_drop(state: *mut system_state);
 
     pub fn bevy_dyn_system_params_drop(param: *mut dyn_system_param);
@@ -1,12 +1,38 @@
 use bevy_mod_ffi_core::*;
 
 unsafe extern "C" {
+    pub fn bevy_deferred_world_get_component_id(
+        deferred_ptr: *mut deferred_world,
+        type_path_ptr: *const u8,
+        type_path_len: usize,
+        out_id: *mut usize,
+    ) -> bool;
+
+    pub fn bevy_deferred_world_get_resource_id(
+        deferred_ptr: *mut deferred_world,
+        type_path_ptr: *const u8,
+        type_path_len: usize,
+        out_id: *mut usize,
+    ) -> bool;
+
     pub fn bevy_deferred_world_query(
         deferred_ptr: *mut deferred_world,
         query_state_ptr: *mut query_state,
         out_query: *mut *mut query,
     ) -> bool;
 
+    pub fn bevy_deferred_world_contains_entity(
+        deferred_ptr: *mut deferred_world,
+        entity_bits: u64,
+    ) -> bool;
+
+    pub fn bevy_deferred_world_get(
+        deferred_ptr: *mut deferred_world,
+        entity_bits: u64,
+        component_id: usize,
+        out_ptr: *mut *const u8,
+    ) -> bool;
+
     pub fn bevy_deferred_world_get_mut(
         deferred_ptr: *mut deferred_world,
         entity_bits: u64,
@@ -14,11 +40,24 @@ unsafe extern "C" {
         out_ptr: *mut *mut u8,
     ) -> bool;
 
+    pub fn bevy_deferred_world_get_resource(
+        deferred_ptr: *mut deferred_world,
+        component_id: usize,
+        out_ptr: *mut *const u8,
+    ) -> bool;
+
     pub fn bevy_deferred_world_get_resource_mut(
         deferred_ptr: *mut deferred_world,
         component_id: usize,
         out_ptr: *mut *mut u8,
     ) -> bool;
 
+    pub fn bevy_deferred_world_commands(
+        deferred_ptr: *mut deferred_world,
+        out_commands: *mut *mut commands,
+    );
+
+    pub fn bevy_deferred_world_reborrow(deferred_ptr: *mut deferred_world) -> *mut deferred_world;
+
     pub fn bevy_deferred_world_drop(deferred_ptr: *mut deferred_world);
 }
@@ -24,6 +24,7 @@ unsafe extern "C" {
         event_name_len: usize,
         f_ptr: *mut (),
         run_observer_fn: RunObserverFn,
+        drop_fn: DropClosureFn,
     ) -> bool;
 
     pub fn bevy_entity_world_mut_trigger(
@@ -33,4 +34,19 @@ unsafe extern "C" {
         event_data_ptr: *const u8,
         event_data_len: usize,
     ) -> bool;
+
+    pub fn bevy_entity_world_mut_insert(
+        entity: *mut entity_world_mut,
+        components_ptr: *const BundleComponent,
+        component_len: usize,
+    ) -> bool;
+
+    pub fn bevy_entity_world_mut_remove(entity: *mut entity_world_mut, component_id: usize)
+    -> bool;
+
+    pub fn bevy_entity_world_mut_world_scope(
+        entity: *mut entity_world_mut,
+        f_ptr: *mut (),
+        run_fn: RunCommandFn,
+    );
 }
@@ -20,6 +20,54 @@ unsafe extern "C" {
         out_ptr: *mut *mut u8,
     ) -> bool;
 
+    pub fn bevy_world_get_resource_mut(
+        world: *mut world,
+        component_id: usize,
+        out_ptr: *mut *mut u8,
+    ) -> bool;
+
+    pub fn bevy_world_contains_resource(world: *mut world, component_id: usize) -> bool;
+
+    pub fn bevy_world_resource_scope(
+        world: *mut world,
+        component_id: usize,
+        f_ptr: *mut (),
+        run_scope_fn: RunResourceScopeFn,
+    ) -> ResourceScopeResult;
+
+    pub fn bevy_world_get(
+        world: *mut world,
+        entity_bits: u64,
+        component_id: usize,
+        out_ptr: *mut *const u8,
+    ) -> bool;
+
+    pub fn bevy_world_get_mut(
+        world: *mut world,
+        entity_bits: u64,
+        component_id: usize,
+        out_ptr: *mut *mut u8,
+    ) -> bool;
+
+    pub fn bevy_world_despawn(world: *mut world, entity_bits: u64) -> bool;
+
+    pub fn bevy_world_entities_len(world: *mut world) -> u32;
+
+    pub fn bevy_world_iter_entities(
+        world: *mut world,
+        out_entities: *mut u64,
+        capacity: usize,
+        out_len: *mut usize,
+    );
+
+    pub fn bevy_world_entity_children(
+        world: *mut world,
+        entity: u64,
+        out_entities: *mut u64,
+        capacity: usize,
+        out_len: *mut usize,
+    ) -> bool;
+
     pub fn bevy_world_get_component_id(
         world: *mut world,
         type_path_ptr: *const u8,
@@ -32,7 +80,7 @@ unsafe extern "C" {
         system_ptr: *mut system,
         input_ptr: *const u8,
         output_ptr: *mut u8,
-    );
+    ) -> bool;
 
     pub fn bevy_world_register_component(
         world: *mut world,
@@ -41,12 +89,34 @@ unsafe extern "C" {
         size: usize,
         align: usize,
         is_table: u8,
+        is_mutable: bool,
+        drop: Option<ComponentDropFn>,
         on_add: Option<ComponentHookFn>,
         on_insert: Option<ComponentHookFn>,
         on_replace: Option<ComponentHookFn>,
         on_remove: Option<ComponentHookFn>,
         on_despawn: Option<ComponentHookFn>,
+        clone_ignore: bool,
+        clone_fn: Option<ComponentCloneFn>,
+        clone_data: *const (),
+        map_entities: Option<MapEntitiesFn>,
         out_id: *mut usize,
+    ) -> RegisterComponentResult;
+
+    pub fn bevy_world_register_required_component(
+        world: *mut world,
+        requiree: usize,
+        required: usize,
+        constructor: RequiredConstructorFn,
+        constructor_data: *const (),
+    ) -> bool;
+
+    pub fn bevy_world_add_component_hook(
+        world: *mut world,
+        component_id: usize,
+        kind: ComponentHookKind,
+        hook_fn: ComponentObserverFn,
+        hook_data: *const (),
     ) -> bool;
 
     pub fn bevy_world_spawn(
@@ -74,6 +144,16 @@ unsafe extern "C" {
         entity_bits: u64,
     ) -> bool;
 
+    pub fn bevy_world_spawn_batch(
+        world: *mut world,
+        component_ids_ptr: *const usize,
+        component_len: usize,
+        data_ptr: *const u8,
+        data_len: usize,
+        count: usize,
+        out_entities: *mut u64,
+    ) -> bool;
+
     pub fn bevy_world_entity_mut(
         world: *mut world,
         entity_bits: u64,
@@ -1,6 +1,6 @@
 #![allow(clippy::missing_safety_doc)]
 
-use bevy::ecs::world::World;
+use bevy::ecs::{hierarchy::ChildOf, world::World};
 use libloading::{Library, Symbol};
 use std::{error::Error, ffi::OsStr, sync::Arc};
 
@@ -19,6 +19,9 @@ impl LoadedLibrary {
         world.remove_resource::<CurrentLibraryHandle>();
 
         if let Some(mut registry) = world.remove_resource::<SharedRegistry>() {
+            registry.remove_library_required_components(self.id);
+            registry.remove_library_component_drops(self.id, world);
+
             if let Some(observers) = registry.take_library_observers(self.id) {
                 world.insert_resource(registry);
 
@@ -49,6 +52,9 @@ pub unsafe fn run(
         id
     };
 
+    // Guests look up `ChildOf` by type path, so it needs to be registered
+    world.register_component::<ChildOf>();
+
     let library_handle = LibraryHandle(guest_lib.clone());
     world.insert_resource(CurrentLibraryHandle(Some(library_handle)));
 
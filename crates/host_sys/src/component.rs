@@ -0,0 +1,409 @@
+use crate::{system::LibraryHandle, LibraryId, SharedRegistry};
+use bevy::{
+    ecs::{
+        change_detection::MaybeLocation,
+        component::{ComponentCloneBehavior, ComponentId},
+        entity::{ComponentCloneCtx, Entity, EntityMapper, SourceComponent},
+        lifecycle::{Add, Despawn, HookContext, Insert, Remove, Replace},
+        observer::{Observer, On},
+        relationship::RelationshipHookMode,
+        system::Commands,
+        world::{DeferredWorld, World},
+    },
+    ptr::OwningPtr,
+};
+use bevy_mod_ffi_core::{
+    deferred_world, entity_mapper, ComponentCloneFn, ComponentDropFn, ComponentHookContext,
+    ComponentHookKind, ComponentObserverFn, MapEntitiesFn,
+};
+use std::{
+    alloc::{self, Layout},
+    ptr::{self, NonNull},
+    sync::RwLock,
+};
+
+/// Guest callbacks used to clone a shared component.
+#[derive(Clone, Copy)]
+pub struct CloneHooks {
+    pub clone_fn: Option<ComponentCloneFn>,
+    pub clone_data: *const (),
+    pub map_entities: Option<MapEntitiesFn>,
+}
+
+// Safety: `clone_data` points to an immutable guest function.
+unsafe impl Send for CloneHooks {}
+unsafe impl Sync for CloneHooks {}
+
+pub fn clone_behavior(ignore: bool) -> ComponentCloneBehavior {
+    if ignore {
+        ComponentCloneBehavior::Ignore
+    } else {
+        ComponentCloneBehavior::Custom(clone_shared_component)
+    }
+}
+
+fn clone_shared_component(source: &SourceComponent, ctx: &mut ComponentCloneCtx) {
+    let component_id = ctx.component_id();
+    let layout = ctx.component_info().layout();
+    let source_entity = ctx.source();
+    let target_entity = ctx.target();
+
+    let source_copy = unsafe { alloc_component(layout) };
+    unsafe {
+        ptr::copy_nonoverlapping(source.ptr().as_ptr(), source_copy, layout.size());
+    }
+
+    // The clone hooks live in the world, so the guest is called once the clone has access to it
+    ctx.queue_deferred(
+        move |world: &mut World, mut mapper: &mut dyn EntityMapper| {
+            let hooks = world
+                .resource::<SharedRegistry>()
+                .clone_hooks
+                .get(&component_id)
+                .copied();
+            let (clone_fn, clone_data, map_entities) = match hooks {
+                Some(hooks) => (hooks.clone_fn, hooks.clone_data, hooks.map_entities),
+                None => (None, ptr::null(), None),
+            };
+            let mapper_ptr = &mut mapper as *mut &mut dyn EntityMapper as *mut entity_mapper;
+
+            let target = match clone_fn {
+                Some(clone_fn) => {
+                    let target = unsafe { alloc_component(layout) };
+                    let is_written = unsafe {
+                        clone_fn(
+                            clone_data,
+                            source_copy,
+                            target,
+                            source_entity.to_bits(),
+                            target_entity.to_bits(),
+                            component_id.index(),
+                            mapper_ptr,
+                        )
+                    };
+                    unsafe { dealloc_component(source_copy, layout) };
+
+                    if !is_written {
+                        unsafe { dealloc_component(target, layout) };
+                        return;
+                    }
+                    target
+                }
+                None => {
+                    if let Some(map_entities) = map_entities {
+                        unsafe { map_entities(source_copy, mapper_ptr) };
+                    }
+                    source_copy
+                }
+            };
+
+            unsafe {
+                let owning_ptr = OwningPtr::new(NonNull::new_unchecked(target));
+                world
+                    .entity_mut(target_entity)
+                    .insert_by_id(component_id, owning_ptr);
+                dealloc_component(target, layout);
+            }
+        },
+    );
+}
+
+pub(crate) fn guest_hook_context(context: &HookContext) -> ComponentHookContext {
+    let caller = context.caller.into_option();
+    let caller_file = caller.map(|location| location.file());
+    ComponentHookContext {
+        entity: context.entity.to_bits(),
+        component_id: context.component_id.index(),
+        caller_file_ptr: caller_file.map_or(ptr::null(), str::as_ptr),
+        caller_file_len: caller_file.map_or(0, str::len),
+        caller_line: caller.map_or(0, |location| location.line()),
+        caller_column: caller.map_or(0, |location| location.column()),
+        relationship_hook_mode: match context.relationship_hook_mode {
+            RelationshipHookMode::Run => 0,
+            RelationshipHookMode::RunIfNotLinked => 1,
+            RelationshipHookMode::Skip => 2,
+        },
+    }
+}
+
+/// A guest hook attached to a component through an observer.
+#[derive(Clone)]
+pub struct ComponentObserverHook {
+    pub hook_fn: ComponentObserverFn,
+    pub hook_data: *const (),
+    pub library_handle: LibraryHandle,
+}
+
+// Safety: `hook_data` points to an immutable guest function.
+unsafe impl Send for ComponentObserverHook {}
+unsafe impl Sync for ComponentObserverHook {}
+
+impl ComponentObserverHook {
+    fn run(
+        &self,
+        deferred: &mut DeferredWorld<'_>,
+        entity: Entity,
+        component_id: ComponentId,
+        caller: MaybeLocation,
+    ) {
+        // The guest takes ownership of the boxed `DeferredWorld` and drops it when the hook returns
+        let deferred_ptr = Box::into_raw(Box::new(deferred.reborrow())) as *mut deferred_world;
+        let hook_context = guest_hook_context(&HookContext {
+            entity,
+            component_id,
+            caller,
+            relationship_hook_mode: RelationshipHookMode::Run,
+        });
+        unsafe {
+            (self.hook_fn)(self.hook_data, deferred_ptr, &hook_context);
+        }
+    }
+}
+
+pub type SpawnHookObserverFn =
+    fn(&mut World, ComponentId, ComponentHookKind, ComponentObserverHook) -> Entity;
+
+/// Spawns an observer that runs a guest hook after any hook set on the component itself.
+pub fn spawn_hook_observer(
+    world: &mut World,
+    component_id: ComponentId,
+    kind: ComponentHookKind,
+    hook: ComponentObserverHook,
+) -> Entity {
+    let observer = match kind {
+        ComponentHookKind::Add => Observer::new(move |on: On<Add>, mut deferred: DeferredWorld| {
+            hook.run(&mut deferred, on.event().entity, component_id, on.caller())
+        }),
+        ComponentHookKind::Insert => {
+            Observer::new(move |on: On<Insert>, mut deferred: DeferredWorld| {
+                hook.run(&mut deferred, on.event().entity, component_id, on.caller())
+            })
+        }
+        ComponentHookKind::Replace => {
+            Observer::new(move |on: On<Replace>, mut deferred: DeferredWorld| {
+                hook.run(&mut deferred, on.event().entity, component_id, on.caller())
+            })
+        }
+        ComponentHookKind::Remove => {
+            Observer::new(move |on: On<Remove>, mut deferred: DeferredWorld| {
+                hook.run(&mut deferred, on.event().entity, component_id, on.caller())
+            })
+        }
+        ComponentHookKind::Despawn => {
+            Observer::new(move |on: On<Despawn>, mut deferred: DeferredWorld| {
+                hook.run(&mut deferred, on.event().entity, component_id, on.caller())
+            })
+        }
+    };
+
+    world.spawn(observer.with_component(component_id)).id()
+}
+
+pub type SpawnRequiredObserverFn = fn(&mut World, ComponentId, ComponentId) -> Entity;
+
+/// Spawns an observer that inserts `required` when `requiree` is added to an entity without it.
+pub fn spawn_required_observer(
+    world: &mut World,
+    requiree: ComponentId,
+    required: ComponentId,
+) -> Entity {
+    let observer = Observer::new(move |on: On<Add>, mut commands: Commands| {
+        let entity = on.event().entity;
+        commands.queue(move |world: &mut World| {
+            insert_required_component(world, entity, requiree, required);
+        });
+    });
+
+    world.spawn(observer.with_component(requiree)).id()
+}
+
+fn insert_required_component(
+    world: &mut World,
+    entity: Entity,
+    requiree: ComponentId,
+    required: ComponentId,
+) {
+    let Ok(entity_ref) = world.get_entity(entity) else {
+        return;
+    };
+    if !entity_ref.contains_id(requiree) || entity_ref.contains_id(required) {
+        return;
+    }
+
+    // The constructor is removed when its library is unloaded
+    let Some(constructor) = world
+        .resource::<SharedRegistry>()
+        .required
+        .get(&requiree)
+        .and_then(|direct| direct.iter().find(|r| r.component_id == required))
+        .copied()
+    else {
+        return;
+    };
+    let layout = world.components().get_info(required).unwrap().layout();
+
+    unsafe {
+        let buffer = alloc_component(layout);
+        (constructor.constructor)(constructor.constructor_data, buffer);
+        world
+            .entity_mut(entity)
+            .insert_by_id(required, OwningPtr::new(NonNull::new_unchecked(buffer)));
+        dealloc_component(buffer, layout);
+    }
+}
+
+/// The number of guest drop functions that can be registered at once.
+const DROP_SLOTS: usize = 64;
+
+/// A guest drop function, called by the host drop function of its slot.
+struct GuestDrop {
+    drop_fn: ComponentDropFn,
+    // Values of the component can outlive the guest's `LoadedLibrary`, so its library stays loaded
+    library_handle: Option<LibraryHandle>,
+    // The number of registered components dropped through this slot
+    components: usize,
+}
+
+static GUEST_DROPS: RwLock<[Option<GuestDrop>; DROP_SLOTS]> =
+    RwLock::new([const { None }; DROP_SLOTS]);
+
+/// Drops a component with the guest drop function in slot `SLOT`.
+///
+/// Component descriptors only store a function pointer, so each slot has its own function.
+unsafe fn drop_trampoline<const SLOT: usize>(ptr: OwningPtr<'_>) {
+    let drop_fn = GUEST_DROPS.read().unwrap()[SLOT]
+        .as_ref()
+        .map(|drop| drop.drop_fn);
+    if let Some(drop_fn) = drop_fn {
+        unsafe { drop_fn(ptr.as_ptr()) };
+    }
+}
+
+macro_rules! drop_trampolines {
+    ($($slot:literal)*) => {
+        [$(drop_trampoline::<$slot> as unsafe fn(OwningPtr<'_>)),*]
+    };
+}
+
+static DROP_TRAMPOLINES: [unsafe fn(OwningPtr<'_>); DROP_SLOTS] = drop_trampolines!(
+    0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15
+    16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31
+    32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47
+    48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63
+);
+
+/// The drop slot of a registered guest component, released once its values are gone.
+#[derive(Clone, Copy)]
+pub struct ComponentDrop {
+    slot: usize,
+    library_id: Option<LibraryId>,
+    is_retired: bool,
+    // Slots live in the copy of this crate that registered the component,
+    // which isn't the one dropping the registry when it's linked into the host
+    release: fn(usize) -> Option<LibraryHandle>,
+}
+
+impl ComponentDrop {
+    /// Marks the component as unused once its values are gone if it was registered by `lib_id`.
+    pub(crate) fn retire(&mut self, lib_id: LibraryId) {
+        if self.library_id == Some(lib_id) {
+            self.is_retired = true;
+        }
+    }
+
+    pub(crate) fn is_retired(&self) -> bool {
+        self.is_retired
+    }
+
+    /// Releases the slot, returning the handle of its library once no component uses it.
+    ///
+    /// The handle must be dropped by the caller, since the library may hold the code of this slot.
+    pub(crate) fn release(self) -> Option<LibraryHandle> {
+        (self.release)(self.slot)
+    }
+}
+
+/// Returns a component descriptor drop function that calls the guest's `drop_fn`,
+/// or `None` if every slot is taken.
+pub(crate) fn guest_drop_fn(
+    drop_fn: ComponentDropFn,
+    library_handle: Option<LibraryHandle>,
+    library_id: Option<LibraryId>,
+) -> Option<(unsafe fn(OwningPtr<'_>), ComponentDrop)> {
+    let mut drops = GUEST_DROPS.write().unwrap();
+
+    // A library that is loaded again shares the drop functions of its first load
+    let slot = match drops.iter().position(|drop| {
+        drop.as_ref()
+            .is_some_and(|drop| ptr::fn_addr_eq(drop.drop_fn, drop_fn))
+    }) {
+        Some(slot) => {
+            drops[slot].as_mut().unwrap().components += 1;
+            slot
+        }
+        None => {
+            let slot = drops.iter().position(Option::is_none)?;
+            drops[slot] = Some(GuestDrop {
+                drop_fn,
+                library_handle,
+                components: 1,
+            });
+            slot
+        }
+    };
+
+    let component_drop = ComponentDrop {
+        slot,
+        library_id,
+        is_retired: false,
+        release: release_drop_slot,
+    };
+    Some((DROP_TRAMPOLINES[slot], component_drop))
+}
+
+fn release_drop_slot(slot: usize) -> Option<LibraryHandle> {
+    let mut drops = GUEST_DROPS.write().unwrap();
+    let drop = drops[slot].as_mut()?;
+    drop.components -= 1;
+    if drop.components > 0 {
+        return None;
+    }
+
+    drops[slot].take().and_then(|drop| drop.library_handle)
+}
+
+pub(crate) unsafe fn alloc_component(layout: Layout) -> *mut u8 {
+    if layout.size() == 0 {
+        layout.align() as *mut u8
+    } else {
+        alloc::alloc(layout)
+    }
+}
+
+pub(crate) unsafe fn dealloc_component(ptr: *mut u8, layout: Layout) {
+    if layout.size() != 0 {
+        alloc::dealloc(ptr, layout);
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_entity_mapper_get_mapped(
+    mapper_ptr: *mut entity_mapper,
+    source_bits: u64,
+) -> u64 {
+    let mapper = unsafe { &mut *(mapper_ptr as *mut &mut dyn EntityMapper) };
+    mapper.get_mapped(Entity::from_bits(source_bits)).to_bits()
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_entity_mapper_set_mapped(
+    mapper_ptr: *mut entity_mapper,
+    source_bits: u64,
+    target_bits: u64,
+) {
+    let mapper = unsafe { &mut *(mapper_ptr as *mut &mut dyn EntityMapper) };
+    mapper.set_mapped(
+        Entity::from_bits(source_bits),
+        Entity::from_bits(target_bits),
+    );
+}
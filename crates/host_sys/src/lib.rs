@@ -1,11 +1,14 @@
 #![allow(clippy::missing_safety_doc)]
 
 use bevy::{
-    ecs::{component::ComponentId, entity::Entity, event::Event, resource::Resource},
+    ecs::{component::ComponentId, entity::Entity, event::Event, resource::Resource, world::World},
     platform::collections::HashMap,
     reflect::TypePath,
 };
-use bevy_mod_ffi_core::ComponentHookFn;
+use bevy_mod_ffi_core::{ComponentHookFn, RequiredConstructorFn};
+
+pub mod component;
+use component::{CloneHooks, ComponentDrop, SpawnHookObserverFn, SpawnRequiredObserverFn};
 
 pub mod query;
 pub use query::*;
@@ -29,14 +32,53 @@ pub struct DynamicHooks {
     pub on_despawn: Option<ComponentHookFn>,
 }
 
-#[derive(Resource, Default)]
+/// A component that is inserted alongside its requiree, built by a guest constructor.
+#[derive(Clone, Copy)]
+pub struct RequiredComponent {
+    pub component_id: ComponentId,
+    pub constructor: RequiredConstructorFn,
+    pub constructor_data: *const (),
+    /// The library the constructor belongs to, which removes it when unloaded.
+    pub library_id: LibraryId,
+}
+
+// Safety: `constructor_data` points to an immutable guest function.
+unsafe impl Send for RequiredComponent {}
+unsafe impl Sync for RequiredComponent {}
+
+#[derive(Resource)]
 pub struct SharedRegistry {
     pub type_path_to_id: HashMap<String, ComponentId>,
     pub hooks: HashMap<ComponentId, DynamicHooks>,
+    pub required: HashMap<ComponentId, Vec<RequiredComponent>>,
+    pub clone_hooks: HashMap<ComponentId, CloneHooks>,
+    pub(crate) drops: HashMap<ComponentId, ComponentDrop>,
     events: HashMap<&'static str, Box<dyn Observable>>,
     library_observers: HashMap<LibraryId, Vec<Entity>>,
     current_library_id: Option<LibraryId>,
     next_library_id: u64,
+    // Set by the host so guest hook observers are built by its copy of this crate, which outlives
+    // any copy loaded alongside a guest library
+    pub(crate) spawn_hook_observer: SpawnHookObserverFn,
+    pub(crate) spawn_required_observer: SpawnRequiredObserverFn,
+}
+
+impl Default for SharedRegistry {
+    fn default() -> Self {
+        Self {
+            type_path_to_id: HashMap::new(),
+            hooks: HashMap::new(),
+            required: HashMap::new(),
+            clone_hooks: HashMap::new(),
+            drops: HashMap::new(),
+            events: HashMap::new(),
+            library_observers: HashMap::new(),
+            current_library_id: None,
+            next_library_id: 0,
+            spawn_hook_observer: component::spawn_hook_observer,
+            spawn_required_observer: component::spawn_required_observer,
+        }
+    }
 }
 
 impl SharedRegistry {
@@ -71,6 +113,46 @@ impl SharedRegistry {
         self.library_observers.remove(&lib_id)
     }
 
+    /// Removes the required components whose constructors belong to `lib_id`.
+    pub fn remove_library_required_components(&mut self, lib_id: LibraryId) {
+        self.required.retain(|_, required| {
+            required.retain(|required| required.library_id != lib_id);
+            !required.is_empty()
+        });
+    }
+
+    /// Releases the drop functions of the components registered by `lib_id` once `world`
+    /// holds none of their values.
+    pub fn remove_library_component_drops(&mut self, lib_id: LibraryId, world: &World) {
+        for component_drop in self.drops.values_mut() {
+            component_drop.retire(lib_id);
+        }
+        self.release_component_drops(world);
+    }
+
+    /// Releases the drop functions of components from unloaded libraries that `world`
+    /// no longer holds any values of, so their slots can be reused.
+    pub fn release_component_drops(&mut self, world: &World) {
+        let released: Vec<_> = self
+            .drops
+            .iter()
+            .filter(|(component_id, component_drop)| {
+                component_drop.is_retired()
+                    && !world.archetypes().iter().any(|archetype| {
+                        !archetype.is_empty() && archetype.contains(**component_id)
+                    })
+            })
+            .map(|(component_id, _)| *component_id)
+            .collect();
+
+        for component_id in released {
+            let component_drop = self.drops.remove(&component_id).unwrap();
+            // The component can't be dropped anymore, so it can't be looked up to insert new values
+            self.type_path_to_id.retain(|_, id| *id != component_id);
+            drop(component_drop.release());
+        }
+    }
+
     pub fn register_event<E: Event + TypePath + Clone + Copy>(&mut self)
     where
         for<'a> E::Trigger<'a>: Default,
@@ -90,4 +172,33 @@ impl SharedRegistry {
     pub fn get_component_id(&self, type_path: &str) -> Option<ComponentId> {
         self.type_path_to_id.get(type_path).copied()
     }
+
+    /// Returns the components required by `ids` (directly or transitively) that are not in `ids`.
+    pub fn required_components(&self, ids: &[ComponentId]) -> Vec<RequiredComponent> {
+        let mut visited = ids.to_vec();
+        let mut out = Vec::new();
+        let mut cursor = 0;
+        while cursor < visited.len() {
+            let id = visited[cursor];
+            cursor += 1;
+
+            for required in self.required.get(&id).into_iter().flatten() {
+                if visited.contains(&required.component_id) {
+                    continue;
+                }
+                visited.push(required.component_id);
+                out.push(*required);
+            }
+        }
+        out
+    }
+}
+
+impl Drop for SharedRegistry {
+    fn drop(&mut self) {
+        // Resources are dropped after the tables of their world, so none of these values are left
+        for (_, component_drop) in self.drops.drain() {
+            drop(component_drop.release());
+        }
+    }
 }
@@ -1,7 +1,7 @@
-use bevy::prelude::*;
-use bevy_mod_ffi_core::{filtered_entity_mut, query_iter};
+use bevy_mod_ffi_core::{filtered_entity_mut, query_iter, FetchComponent};
+use std::{ptr, slice};
 
-use super::SharedQueryIter;
+use super::{fetch_component, SharedQueryIter};
 
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn bevy_query_iter_next(
@@ -23,6 +23,48 @@ pub unsafe extern "C" fn bevy_query_iter_next(
     true
 }
 
+/// Fills `out_entities` with up to `capacity` entities and `out_components` with
+/// `fetches_len` component pointers per entity, in the order of `fetches`.
+///
+/// Components that can't be fetched for an entity are written as null pointers.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_query_iter_next_batch(
+    iter_ptr: *mut query_iter,
+    fetches_ptr: *const FetchComponent,
+    fetches_len: usize,
+    out_entities: *mut u64,
+    out_components: *mut *mut u8,
+    capacity: usize,
+    out_len: *mut usize,
+) -> bool {
+    let shared_iter = unsafe { &mut *(iter_ptr as *mut SharedQueryIter) };
+    let fetches = unsafe { slice::from_raw_parts(fetches_ptr, fetches_len) };
+    let entities = unsafe { slice::from_raw_parts_mut(out_entities, capacity) };
+    let components = unsafe { slice::from_raw_parts_mut(out_components, capacity * fetches_len) };
+
+    let mut len = 0;
+    while len < capacity {
+        let Some(mut entity_mut) = shared_iter.next() else {
+            break;
+        };
+
+        entities[len] = entity_mut.id().to_bits();
+
+        let row = &mut components[len * fetches_len..(len + 1) * fetches_len];
+        for (fetch, out_ptr) in fetches.iter().zip(row) {
+            *out_ptr = fetch_component(&mut entity_mut, fetch).unwrap_or(ptr::null_mut());
+        }
+
+        len += 1;
+    }
+
+    unsafe {
+        *out_len = len;
+    }
+
+    true
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn bevy_query_iter_drop(iter_ptr: *mut query_iter) {
     let _ = unsafe { Box::from_raw(iter_ptr as *mut SharedQueryIter) };
@@ -0,0 +1,147 @@
+use bevy::ecs::{
+    component::{ComponentId, Tick},
+    query::FilteredAccess,
+    world::unsafe_world_cell::UnsafeWorldCell,
+};
+use bevy_mod_ffi_core::{query, query_lens, QueryTerm, QueryTermKind};
+use std::slice;
+
+use super::{SharedQuery, SharedQueryState};
+
+/// A [`QueryLens`](bevy::ecs::system::QueryLens) over guest queries, which owns
+/// the transmuted or joined state its queries are built from.
+pub struct SharedQueryLens<'w> {
+    world: UnsafeWorldCell<'w>,
+    state: SharedQueryState,
+    last_run: Tick,
+    this_run: Tick,
+}
+
+impl<'w> SharedQueryLens<'w> {
+    fn new(query: &SharedQuery<'w, '_>, state: SharedQueryState) -> Self {
+        Self {
+            world: query.world,
+            state,
+            last_run: query.last_run,
+            this_run: query.this_run,
+        }
+    }
+
+    pub fn query(&mut self) -> SharedQuery<'w, '_> {
+        // SAFETY: The lens state only accesses what the queries it was built from do.
+        unsafe { SharedQuery::new(self.world, &self.state, self.last_run, self.this_run) }
+    }
+}
+
+/// Reads the terms of a guest lens filter, which may be null when it has none.
+unsafe fn filter_terms<'a>(filter_ptr: *const QueryTerm, filter_len: usize) -> &'a [QueryTerm] {
+    if filter_ptr.is_null() {
+        &[]
+    } else {
+        unsafe { slice::from_raw_parts(filter_ptr, filter_len) }
+    }
+}
+
+/// Returns `true` if the terms of a guest lens filter only access what `state` does,
+/// like [`QueryState::transmute_filtered`](bevy::ecs::query::QueryState::transmute_filtered) checks.
+fn is_filter_allowed(state: &SharedQueryState, filter: &[QueryTerm]) -> bool {
+    let mut access = FilteredAccess::default();
+    for term in filter {
+        let component_id = ComponentId::new(term.component_id);
+        match term.kind {
+            QueryTermKind::Ref => access.add_component_read(component_id),
+            QueryTermKind::Mut => access.add_component_write(component_id),
+            QueryTermKind::With => access.and_with(component_id),
+            QueryTermKind::Without => access.and_without(component_id),
+        }
+    }
+
+    access.is_subset(state.component_access())
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_query_transmute_lens(
+    query_ptr: *mut query,
+    filter_ptr: *const QueryTerm,
+    filter_len: usize,
+    out_lens: *mut *mut query_lens,
+) -> bool {
+    if query_ptr.is_null() {
+        return false;
+    }
+
+    let query = unsafe { &mut *(query_ptr as *mut SharedQuery) };
+    let filter = unsafe { filter_terms(filter_ptr, filter_len) };
+
+    // `FilteredEntityMut` keeps the full access of the original query,
+    // so the guest narrows it by only fetching a subset of its components.
+    // Like in Bevy, the lens matches the same archetypes, so its filter doesn't narrow them.
+    let state = query.state.transmute(query.world);
+    if !is_filter_allowed(&state, filter) {
+        return false;
+    }
+
+    let lens = SharedQueryLens::new(query, state);
+
+    unsafe {
+        *out_lens = Box::into_raw(Box::new(lens)) as *mut query_lens;
+    }
+
+    true
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_query_join(
+    query_ptr: *mut query,
+    other_ptr: *mut query,
+    filter_ptr: *const QueryTerm,
+    filter_len: usize,
+    out_lens: *mut *mut query_lens,
+) -> bool {
+    if query_ptr.is_null() || other_ptr.is_null() {
+        return false;
+    }
+
+    let query = unsafe { &mut *(query_ptr as *mut SharedQuery) };
+    let other = unsafe { &mut *(other_ptr as *mut SharedQuery) };
+    let filter = unsafe { filter_terms(filter_ptr, filter_len) };
+
+    // The joined state merges the `FilteredAccess` of both queries and only
+    // matches archetypes present in each.
+    let state = query.state.join(query.world, other.state);
+    if !is_filter_allowed(&state, filter) {
+        return false;
+    }
+
+    let lens = SharedQueryLens::new(query, state);
+
+    unsafe {
+        *out_lens = Box::into_raw(Box::new(lens)) as *mut query_lens;
+    }
+
+    true
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_query_lens_query(
+    lens_ptr: *mut query_lens,
+    out_query: *mut *mut query,
+) -> bool {
+    if lens_ptr.is_null() {
+        return false;
+    }
+
+    let lens = unsafe { &mut *(lens_ptr as *mut SharedQueryLens) };
+    let query = lens.query();
+
+    unsafe {
+        *out_query = Box::into_raw(Box::new(query)) as *mut query;
+    }
+
+    true
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_query_lens_drop(lens_ptr: *mut query_lens) {
+    let _ = unsafe { Box::from_raw(lens_ptr as *mut SharedQueryLens) };
+}
@@ -1,28 +1,389 @@
 use bevy::{
     ecs::{
+        archetype::ArchetypeEntity,
+        change_detection::MaybeLocation,
+        component::{ComponentId, StorageType, Tick},
         prelude::*,
-        query::{QueryIter, QueryState},
-        world::FilteredEntityMut,
+        query::{QueryCombinationIter, QueryState},
+        storage::TableId,
+        world::{unsafe_world_cell::UnsafeWorldCell, FilteredEntityMut, FilteredEntityRef},
     },
     prelude::*,
 };
-use bevy_mod_ffi_core::{filtered_entity_mut, query, query_iter};
+use bevy_mod_ffi_core::{
+    filtered_entity_mut, query, query_combination_iter, query_iter, CompareFn, FetchComponent,
+    RunChunkFn,
+};
+use std::{ptr, slice};
 
 type SharedQueryBuilder<'w> = QueryBuilder<'w, FilteredEntityMut<'static, 'static>>;
-type SharedQueryState = QueryState<FilteredEntityMut<'static, 'static>>;
-type SharedQueryIter = QueryIter<'static, 'static, FilteredEntityMut<'static, 'static>, ()>;
+pub(crate) type SharedQueryState = QueryState<FilteredEntityMut<'static, 'static>>;
+type SharedQueryIter = Box<dyn Iterator<Item = FilteredEntityMut<'static, 'static>>>;
+type SharedCombinationIter = Box<dyn CombinationIter>;
+
+/// A guest query, which keeps the [`QueryState`] and world it runs on
+/// so chunked iteration can walk the matched tables directly.
+pub struct SharedQuery<'w, 's> {
+    world: UnsafeWorldCell<'w>,
+    state: &'s SharedQueryState,
+    last_run: Tick,
+    this_run: Tick,
+}
+
+impl<'w, 's> SharedQuery<'w, 's> {
+    /// # Safety
+    /// `state` must be updated for `world`, and the caller must have access to
+    /// every component `state` accesses for `'w`.
+    pub unsafe fn new(
+        world: UnsafeWorldCell<'w>,
+        state: &'s SharedQueryState,
+        last_run: Tick,
+        this_run: Tick,
+    ) -> Self {
+        Self {
+            world,
+            state,
+            last_run,
+            this_run,
+        }
+    }
+
+    pub fn query(&mut self) -> Query<'_, 's, FilteredEntityMut<'static, 'static>> {
+        // SAFETY: The caller of `new` ensured the access, which `&mut self` keeps unique.
+        unsafe {
+            self.state
+                .query_unchecked_manual_with_ticks(self.world, self.last_run, self.this_run)
+        }
+    }
+
+    /// Returns `true` if the query's access allows every fetch.
+    fn can_fetch(&self, fetches: &[FetchComponent]) -> bool {
+        let access = self.state.component_access().access();
+        let components = self.world.components();
+
+        fetches.iter().all(|fetch| {
+            let component_id = ComponentId::new(fetch.component_id);
+            if fetch.is_mut {
+                access.has_component_write(component_id)
+                    && components
+                        .get_info(component_id)
+                        .is_some_and(|info| info.mutable())
+            } else {
+                access.has_component_read(component_id)
+            }
+        })
+    }
+
+    /// Returns `true` if every term of the query is stored in tables,
+    /// so each matched table is matched as a whole.
+    fn is_dense(&self) -> bool {
+        let components = self.world.components();
+        let is_table = |component_id| {
+            components
+                .get_info(component_id)
+                .is_some_and(|info| info.storage_type() == StorageType::Table)
+        };
+
+        let access = self.state.component_access();
+        let Ok(mut component_accesses) = access.access().try_iter_component_access() else {
+            return false;
+        };
+
+        component_accesses.all(|component_access| is_table(*component_access.index()))
+            && access.with_filters().all(is_table)
+            && access.without_filters().all(is_table)
+    }
+
+    /// Collects the chunks of matched entities whose fetched components are contiguous.
+    ///
+    /// Returns `None` if a fetch isn't allowed by the query or is missing from a matched entity.
+    ///
+    /// # Safety
+    /// The chunks must not outlive the query's access.
+    unsafe fn chunks(&self, fetches: &[FetchComponent]) -> Option<Chunks> {
+        if !self.can_fetch(fetches) {
+            return None;
+        }
+
+        let tables = unsafe { &self.world.storages().tables };
+        let mut chunks = Chunks::default();
+
+        if self.is_dense() {
+            for table_id in self.state.matched_tables() {
+                let table = &tables[table_id];
+                let Some(first) = table.entities().first() else {
+                    continue;
+                };
+                let table_row = self.world.entities().get(*first)?.table_row;
+
+                let columns = fetches
+                    .iter()
+                    .map(|fetch| {
+                        let component_id = ComponentId::new(fetch.component_id);
+                        unsafe { table.get_component(component_id, table_row) }
+                            .map(|ptr| ptr.as_ptr())
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+
+                chunks.push(
+                    Some((table_id, 0)),
+                    table.entities().iter().copied(),
+                    columns,
+                );
+            }
+
+            return Some(chunks);
+        }
+
+        let archetypes = self.world.archetypes();
+        for archetype_id in self.state.matched_archetypes() {
+            let archetype = &archetypes[archetype_id];
+            let table = &tables[archetype.table_id()];
+
+            let is_table = fetches.iter().all(|fetch| {
+                archetype.get_storage_type(ComponentId::new(fetch.component_id))
+                    == Some(StorageType::Table)
+            });
+
+            if !is_table {
+                for archetype_entity in archetype.entities() {
+                    let entity = self
+                        .world
+                        .get_entity_with_ticks(archetype_entity.id(), self.last_run, self.this_run)
+                        .ok()?;
+
+                    let columns = fetches
+                        .iter()
+                        .map(|fetch| {
+                            let component_id = ComponentId::new(fetch.component_id);
+                            if fetch.is_mut {
+                                unsafe { entity.get_mut_by_id(component_id) }
+                                    .ok()
+                                    .map(|ptr| ptr.into_inner().as_ptr())
+                            } else {
+                                unsafe { entity.get_by_id(component_id) }.map(|ptr| ptr.as_ptr())
+                            }
+                        })
+                        .collect::<Option<Vec<_>>>()?;
+
+                    chunks.push(None, [archetype_entity.id()], columns);
+                }
+
+                continue;
+            }
+
+            for run in archetype
+                .entities()
+                .chunk_by(|a, b| a.table_row().index() + 1 == b.table_row().index())
+            {
+                let table_row = run[0].table_row();
+                let columns = fetches
+                    .iter()
+                    .map(|fetch| {
+                        let component_id = ComponentId::new(fetch.component_id);
+                        unsafe { table.get_component(component_id, table_row) }
+                            .map(|ptr| ptr.as_ptr())
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+
+                chunks.push(
+                    Some((archetype.table_id(), table_row.index())),
+                    run.iter().map(ArchetypeEntity::id),
+                    columns,
+                );
+            }
+        }
+
+        Some(chunks)
+    }
+}
+
+/// Chunks of matched entities, which own the bits of their entity ids
+/// since `Entity` doesn't guarantee the layout of its bits.
+#[derive(Default)]
+pub(crate) struct Chunks {
+    chunks: Vec<Chunk>,
+    entities: Vec<Box<[u64]>>,
+}
+
+impl Chunks {
+    fn push(
+        &mut self,
+        table: Option<(TableId, usize)>,
+        entities: impl IntoIterator<Item = Entity>,
+        columns: Vec<*mut u8>,
+    ) {
+        let entities: Box<[u64]> = entities.into_iter().map(Entity::to_bits).collect();
+
+        self.chunks.push(Chunk {
+            table,
+            entities: entities.as_ptr(),
+            columns,
+            len: entities.len(),
+        });
+        self.entities.push(entities);
+    }
+}
+
+/// Entities whose fetched components are stored contiguously, starting at `columns`.
+pub(crate) struct Chunk {
+    /// The table and first row of the chunk, if it is stored in a table.
+    table: Option<(TableId, usize)>,
+    entities: *const u64,
+    columns: Vec<*mut u8>,
+    len: usize,
+}
+
+// SAFETY: Chunks point into world storage, and parallel batches only access disjoint rows.
+unsafe impl Sync for Chunk {}
+
+impl Chunk {
+    /// Marks the mutably fetched components of `len` rows from `offset` as changed.
+    ///
+    /// Chunks outside of tables were already marked when they were fetched.
+    ///
+    /// # Safety
+    /// The rows must not be accessed elsewhere while they are marked.
+    unsafe fn mark_changed(
+        &self,
+        world: UnsafeWorldCell,
+        this_run: Tick,
+        fetches: &[FetchComponent],
+        offset: usize,
+        len: usize,
+    ) {
+        let Some((table_id, row)) = self.table else {
+            return;
+        };
+
+        let table = unsafe { &world.storages().tables[table_id] };
+        let caller = MaybeLocation::caller();
+        let rows = row + offset..row + offset + len;
+
+        for fetch in fetches.iter().filter(|fetch| fetch.is_mut) {
+            let component_id = ComponentId::new(fetch.component_id);
+
+            for tick in &table.get_changed_ticks_slice_for(component_id).unwrap()[rows.clone()] {
+                unsafe { *tick.get() = this_run };
+            }
+
+            if let (Some(Some(changed_by)), Some(caller)) = (
+                table.get_changed_by_slice_for(component_id).into_option(),
+                caller.into_option(),
+            ) {
+                for changed_by in &changed_by[rows.clone()] {
+                    unsafe { *changed_by.get() = caller };
+                }
+            }
+        }
+    }
+}
+
+/// Object-safe wrapper over [`QueryCombinationIter`] so combinations of any supported size
+/// can be handed across FFI behind a single pointer type.
+trait CombinationIter {
+    fn fetch_next(
+        &mut self,
+        fetches: &[FetchComponent],
+        out_entities: &mut [u64],
+        out_components: &mut [*mut u8],
+    ) -> bool;
+}
+
+impl<const K: usize> CombinationIter
+    for QueryCombinationIter<'_, '_, FilteredEntityMut<'static, 'static>, (), K>
+{
+    fn fetch_next(
+        &mut self,
+        fetches: &[FetchComponent],
+        out_entities: &mut [u64],
+        out_components: &mut [*mut u8],
+    ) -> bool {
+        let Some(entities) = QueryCombinationIter::fetch_next(self) else {
+            return false;
+        };
+
+        for (index, mut entity_mut) in entities.into_iter().enumerate() {
+            out_entities[index] = entity_mut.id().to_bits();
+
+            let row = &mut out_components[index * fetches.len()..(index + 1) * fetches.len()];
+            for (fetch, out_ptr) in fetches.iter().zip(row) {
+                *out_ptr = fetch_component(&mut entity_mut, fetch).unwrap_or(ptr::null_mut());
+            }
+        }
+
+        true
+    }
+}
 
 pub mod builder;
 pub mod iter;
+pub mod lens;
+pub mod par_iter;
 pub mod state;
 
+fn fetch_component(entity_mut: &mut FilteredEntityMut, fetch: &FetchComponent) -> Option<*mut u8> {
+    let component_id = ComponentId::new(fetch.component_id);
+    if fetch.is_mut {
+        entity_mut
+            .get_mut_by_id(component_id)
+            .map(|ptr| ptr.into_inner().as_ptr())
+    } else {
+        entity_mut.get_by_id(component_id).map(|ptr| ptr.as_ptr())
+    }
+}
+
 #[unsafe(no_mangle)]
 unsafe extern "C" fn bevy_query_iter_mut(
     query_ptr: *mut query,
     out_iter: *mut *mut query_iter,
 ) -> bool {
-    let query = unsafe { &mut *(query_ptr as *mut Query<FilteredEntityMut>) };
-    let iter = query.iter_mut();
+    let query = unsafe { &mut *(query_ptr as *mut SharedQuery) };
+    let iter: SharedQueryIter = Box::new(query.query().into_iter());
+
+    unsafe {
+        *out_iter = Box::into_raw(Box::new(iter)) as *mut query_iter;
+    }
+
+    true
+}
+
+/// Creates an iterator over the query sorted by the component `component_id`,
+/// using `compare_fn` to order two component pointers.
+///
+/// The sort is stable. Entities missing the component are ordered first.
+#[unsafe(no_mangle)]
+unsafe extern "C" fn bevy_query_iter_mut_sort_by(
+    query_ptr: *mut query,
+    component_id: usize,
+    f_ptr: *mut (),
+    compare_fn: CompareFn,
+    out_iter: *mut *mut query_iter,
+) -> bool {
+    let query = unsafe { &mut *(query_ptr as *mut SharedQuery) };
+    let component_id = ComponentId::new(component_id);
+
+    let iter: SharedQueryIter = Box::new(query.query().into_iter().sort_by::<FilteredEntityRef>(
+        |a, b| match (a.get_by_id(component_id), b.get_by_id(component_id)) {
+            (Some(a), Some(b)) => unsafe { compare_fn(f_ptr, a.as_ptr(), b.as_ptr()) }.cmp(&0),
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        },
+    ));
+
+    unsafe {
+        *out_iter = Box::into_raw(Box::new(iter)) as *mut query_iter;
+    }
+
+    true
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn bevy_query_iter_mut_sort_by_entity(
+    query_ptr: *mut query,
+    out_iter: *mut *mut query_iter,
+) -> bool {
+    let query = unsafe { &mut *(query_ptr as *mut SharedQuery) };
+    let iter: SharedQueryIter = Box::new(query.query().into_iter().sort::<Entity>());
 
     unsafe {
         *out_iter = Box::into_raw(Box::new(iter)) as *mut query_iter;
@@ -31,16 +392,66 @@ unsafe extern "C" fn bevy_query_iter_mut(
     true
 }
 
+#[unsafe(no_mangle)]
+unsafe extern "C" fn bevy_query_iter_combinations_mut(
+    query_ptr: *mut query,
+    k: usize,
+    out_iter: *mut *mut query_combination_iter,
+) -> bool {
+    let query = unsafe { &mut *(query_ptr as *mut SharedQuery) };
+
+    let iter: SharedCombinationIter = match k {
+        1 => Box::new(query.query().iter_combinations_inner::<1>()),
+        2 => Box::new(query.query().iter_combinations_inner::<2>()),
+        3 => Box::new(query.query().iter_combinations_inner::<3>()),
+        4 => Box::new(query.query().iter_combinations_inner::<4>()),
+        5 => Box::new(query.query().iter_combinations_inner::<5>()),
+        6 => Box::new(query.query().iter_combinations_inner::<6>()),
+        7 => Box::new(query.query().iter_combinations_inner::<7>()),
+        8 => Box::new(query.query().iter_combinations_inner::<8>()),
+        _ => return false,
+    };
+
+    unsafe {
+        *out_iter = Box::into_raw(Box::new(iter)) as *mut query_combination_iter;
+    }
+
+    true
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn bevy_query_combination_iter_fetch_next(
+    iter_ptr: *mut query_combination_iter,
+    fetches_ptr: *const FetchComponent,
+    fetches_len: usize,
+    out_entities: *mut u64,
+    out_entities_len: usize,
+    out_components: *mut *mut u8,
+) -> bool {
+    let iter = unsafe { &mut *(iter_ptr as *mut SharedCombinationIter) };
+    let fetches = unsafe { slice::from_raw_parts(fetches_ptr, fetches_len) };
+    let entities = unsafe { slice::from_raw_parts_mut(out_entities, out_entities_len) };
+    let components =
+        unsafe { slice::from_raw_parts_mut(out_components, out_entities_len * fetches_len) };
+
+    iter.fetch_next(fetches, entities, components)
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn bevy_query_combination_iter_drop(iter_ptr: *mut query_combination_iter) {
+    let _ = unsafe { Box::from_raw(iter_ptr as *mut SharedCombinationIter) };
+}
+
 #[unsafe(no_mangle)]
 unsafe extern "C" fn bevy_query_get_mut(
     query_ptr: *mut query,
     entity_id: u64,
     out_entity: *mut *mut filtered_entity_mut,
 ) -> bool {
-    let query = unsafe { &mut *(query_ptr as *mut Query<FilteredEntityMut>) };
+    let query = unsafe { &mut *(query_ptr as *mut SharedQuery) };
     let entity = Entity::from_bits(entity_id);
 
-    let filtered_entity = match query.get_mut(entity) {
+    let filtered_entity = match query.query().get_inner(entity) {
         Ok(e) => e,
         Err(_) => return false,
     };
@@ -52,7 +463,61 @@ unsafe extern "C" fn bevy_query_get_mut(
     true
 }
 
+/// Calls `run_chunk_fn` once per run of matched entities stored contiguously in a table.
+///
+/// Each column pointer points to the component of the first entity in the chunk.
+/// Dense queries pass each matched table as a single chunk. Otherwise each archetype is split
+/// into runs of consecutive table rows, and entities with a fetched sparse set component
+/// are passed as chunks of one.
+#[unsafe(no_mangle)]
+unsafe extern "C" fn bevy_query_for_each_chunk(
+    query_ptr: *mut query,
+    fetches_ptr: *const FetchComponent,
+    fetches_len: usize,
+    f_ptr: *mut (),
+    run_chunk_fn: RunChunkFn,
+) -> bool {
+    let query = unsafe { &mut *(query_ptr as *mut SharedQuery) };
+    let fetches = unsafe { slice::from_raw_parts(fetches_ptr, fetches_len) };
+
+    let Some(chunks) = (unsafe { query.chunks(fetches) }) else {
+        return false;
+    };
+
+    for chunk in &chunks.chunks {
+        unsafe {
+            chunk.mark_changed(query.world, query.this_run, fetches, 0, chunk.len);
+            run_chunk_fn(f_ptr, chunk.entities, chunk.columns.as_ptr(), chunk.len);
+        }
+    }
+
+    true
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn bevy_query_get_component_id(
+    query_ptr: *mut query,
+    type_path_ptr: *const u8,
+    type_path_len: usize,
+    out_id: *mut usize,
+) -> bool {
+    let query = unsafe { &*(query_ptr as *const SharedQuery) };
+
+    // SAFETY: Like `DeferredWorld`, this only reads the component and type registries
+    // through the shared `World`, which queries never access mutably.
+    let world = unsafe { query.world.world() };
+    let Some(component_id) = crate::world::component_id(world, type_path_ptr, type_path_len) else {
+        return false;
+    };
+
+    unsafe {
+        *out_id = component_id.index();
+    }
+
+    true
+}
+
 #[unsafe(no_mangle)]
 unsafe extern "C" fn bevy_query_drop(query_ptr: *mut query) {
-    let _ = unsafe { Box::from_raw(query_ptr as *mut Query<FilteredEntityMut>) };
+    let _ = unsafe { Box::from_raw(query_ptr as *mut SharedQuery) };
 }
@@ -0,0 +1,81 @@
+use bevy::{
+    ecs::component::ComponentId,
+    tasks::{ComputeTaskPool, TaskPool},
+};
+use bevy_mod_ffi_core::{query, FetchComponent, RunChunkFn};
+use std::slice;
+
+use super::SharedQuery;
+
+/// Splits the chunks of matched entities into batches of up to `batch_size` entities,
+/// then calls `run_batch_fn` from the compute task pool for each batch.
+///
+/// Batches receive `fetches_len` component pointers per entity, in the order of `fetches`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_query_par_for_each(
+    query_ptr: *mut query,
+    fetches_ptr: *const FetchComponent,
+    fetches_len: usize,
+    batch_size: usize,
+    f_ptr: *mut (),
+    run_batch_fn: RunChunkFn,
+) -> bool {
+    if batch_size == 0 {
+        return false;
+    }
+
+    let query = unsafe { &mut *(query_ptr as *mut SharedQuery) };
+    let fetches = unsafe { slice::from_raw_parts(fetches_ptr, fetches_len) };
+
+    let Some(chunks) = (unsafe { query.chunks(fetches) }) else {
+        return false;
+    };
+
+    let components = query.world.components();
+    let sizes: Vec<usize> = fetches
+        .iter()
+        .map(|fetch| {
+            components
+                .get_info(ComponentId::new(fetch.component_id))
+                .map_or(0, |info| info.layout().size())
+        })
+        .collect();
+
+    let world = query.world;
+    let this_run = query.this_run;
+    let f_ptr = f_ptr as usize;
+    let sizes = &sizes;
+
+    ComputeTaskPool::get_or_init(TaskPool::default).scope(|scope| {
+        for chunk in &chunks.chunks {
+            for offset in (0..chunk.len).step_by(batch_size) {
+                let len = batch_size.min(chunk.len - offset);
+
+                scope.spawn(async move {
+                    let mut components = Vec::with_capacity(len * fetches_len);
+                    for row in offset..offset + len {
+                        components.extend(
+                            chunk
+                                .columns
+                                .iter()
+                                .zip(sizes)
+                                .map(|(column, size)| unsafe { column.add(row * size) }),
+                        );
+                    }
+
+                    unsafe {
+                        chunk.mark_changed(world, this_run, fetches, offset, len);
+                        run_batch_fn(
+                            f_ptr as _,
+                            chunk.entities.add(offset),
+                            components.as_ptr(),
+                            len,
+                        );
+                    }
+                });
+            }
+        }
+    });
+
+    true
+}
@@ -16,7 +16,7 @@ pub unsafe extern "C" fn bevy_query_state_iter_mut(
     let world = unsafe { &mut *(world_ptr as *mut World) };
     let state = unsafe { &mut *(query_ptr as *mut SharedQueryState) };
 
-    let iter: SharedQueryIter = state.iter_mut(world);
+    let iter: SharedQueryIter = Box::new(state.iter_mut(world));
 
     unsafe {
         *out_iter = Box::into_raw(Box::new(iter)) as *mut query_iter;
@@ -25,6 +25,23 @@ pub unsafe extern "C" fn bevy_query_state_iter_mut(
     true
 }
 
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_query_state_update_archetypes(
+    world_ptr: *mut world,
+    query_ptr: *mut query_state,
+) -> bool {
+    if world_ptr.is_null() || query_ptr.is_null() {
+        return false;
+    }
+
+    let world = unsafe { &*(world_ptr as *const World) };
+    let state = unsafe { &mut *(query_ptr as *mut SharedQueryState) };
+
+    state.update_archetypes(world);
+
+    true
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn bevy_query_state_drop(query_ptr: *mut query_state) {
     let _ = unsafe { Box::from_raw(query_ptr as *mut SharedQueryState) };
@@ -6,7 +6,10 @@ use bevy::{
     },
     prelude::*,
 };
-use bevy_mod_ffi_core::{dyn_system_param, system, system_state, world, RunSystemFn};
+use bevy_mod_ffi_core::{
+    dyn_system_param, system, system_state, world, DropClosureFn, RunSystemFn,
+};
+use std::ptr;
 
 pub mod observer;
 pub use observer::{CurrentLibraryHandle, LibraryHandle};
@@ -17,6 +20,62 @@ pub type SharedSystem = Box<dyn System<In = In<SystemIn>, Out = ()>>;
 
 pub type SharedSystemState = SystemState<(Vec<DynSystemParam<'static, 'static>>,)>;
 
+/// A closure owned by a guest, which is dropped with the guest's drop function.
+pub struct GuestClosure {
+    ptr: *mut (),
+    drop_fn: DropClosureFn,
+    // Keeps the guest library loaded until the closure has been dropped
+    _library_handle: Option<LibraryHandle>,
+}
+
+// SAFETY: Guest closures are only run by the host with access to the world.
+unsafe impl Send for GuestClosure {}
+unsafe impl Sync for GuestClosure {}
+
+impl GuestClosure {
+    /// # Safety
+    /// `drop_fn` must be safe to call once with `ptr`.
+    pub unsafe fn new(
+        ptr: *mut (),
+        drop_fn: DropClosureFn,
+        library_handle: Option<LibraryHandle>,
+    ) -> Self {
+        Self {
+            ptr,
+            drop_fn,
+            _library_handle: library_handle,
+        }
+    }
+
+    /// Takes ownership of a closure created by the guest library currently loading into `world`,
+    /// keeping that library loaded until the closure is dropped.
+    ///
+    /// Returns `None`, dropping the closure, if no guest library is loading.
+    ///
+    /// # Safety
+    /// `drop_fn` must be safe to call once with `ptr`.
+    pub unsafe fn with_current_library(
+        world: &World,
+        ptr: *mut (),
+        drop_fn: DropClosureFn,
+    ) -> Option<Self> {
+        let library_handle = CurrentLibraryHandle::get(world);
+        let has_library = library_handle.is_some();
+        let closure = unsafe { Self::new(ptr, drop_fn, library_handle) };
+        has_library.then_some(closure)
+    }
+
+    pub fn as_ptr(&self) -> *mut () {
+        self.ptr
+    }
+}
+
+impl Drop for GuestClosure {
+    fn drop(&mut self) {
+        unsafe { (self.drop_fn)(self.ptr) };
+    }
+}
+
 pub struct SystemIn {
     pub input_ptr: *mut u8,
     pub output_ptr: *mut u8,
@@ -49,6 +108,14 @@ pub unsafe extern "C" fn bevy_system_state_get(
     }
 }
 
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_system_state_params_drop(
+    params_ptr: *mut *mut dyn_system_param,
+    params_len: usize,
+) {
+    let _ = unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(params_ptr, params_len)) };
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn bevy_system_state_apply(
     world_ptr: *mut world,
@@ -70,11 +137,11 @@ pub unsafe extern "C" fn bevy_system_state_build(
     state_ptr: *mut system_state,
     f_ptr: *mut (),
     run_system_fn: RunSystemFn,
+    drop_fn: DropClosureFn,
     out_ptr: *mut *mut system,
 ) {
     let state: Box<SharedSystemState> = unsafe { Box::from_raw(state_ptr as _) };
-
-    let f_ptr_n = f_ptr as usize;
+    let closure = unsafe { GuestClosure::new(f_ptr, drop_fn, None) };
 
     let bevy_system =
         state.build_system_with_input(move |input: In<SystemIn>, params: Vec<DynSystemParam>| {
@@ -88,7 +155,7 @@ pub unsafe extern "C" fn bevy_system_state_build(
 
             unsafe {
                 run_system_fn(
-                    f_ptr_n as _,
+                    closure.as_ptr(),
                     pointers_ptr,
                     len,
                     input.input_ptr,
@@ -102,3 +169,8 @@ pub unsafe extern "C" fn bevy_system_state_build(
         *out_ptr = Box::into_raw(boxed) as *mut system;
     }
 }
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_system_drop(system_ptr: *mut system) {
+    let _ = unsafe { Box::from_raw(system_ptr as *mut SharedSystem) };
+}
@@ -1,4 +1,4 @@
-use crate::{SharedRegistry, SharedSystemState};
+use crate::{GuestClosure, SharedRegistry, SharedSystemState};
 use bevy::{
     ecs::{
         entity::Entity, event::Event, observer::On, prelude::*, system::DynSystemParam,
@@ -7,7 +7,7 @@ use bevy::{
     prelude::*,
     reflect::TypePath,
 };
-use bevy_mod_ffi_core::{dyn_system_param, system_state, world, RunObserverFn};
+use bevy_mod_ffi_core::{dyn_system_param, system_state, world, DropClosureFn, RunObserverFn};
 use std::{any::Any, ffi::CStr, marker::PhantomData, slice, sync::Arc};
 
 #[derive(Clone)]
@@ -26,18 +26,16 @@ pub trait Observable: Send + Sync + 'static {
         &self,
         world: &mut World,
         state: Box<SharedSystemState>,
-        f_ptr: usize,
+        closure: GuestClosure,
         run_observer_fn: RunObserverFn,
-        library_handle: LibraryHandle,
     ) -> Entity;
 
     fn observe_entity(
         &self,
         entity: EntityWorldMut,
         state: Box<SharedSystemState>,
-        f_ptr: usize,
+        closure: GuestClosure,
         run_observer_fn: RunObserverFn,
-        library_handle: LibraryHandle,
     );
 
     fn trigger(&self, world: &mut World, event_data: &[u8]);
@@ -75,14 +73,11 @@ where
         &self,
         world: &mut World,
         state: Box<SharedSystemState>,
-        f_ptr: usize,
+        closure: GuestClosure,
         run_observer_fn: RunObserverFn,
-        library_handle: LibraryHandle,
     ) -> Entity {
         let observer_system =
             state.build_any_system(move |on: On<E>, params: Vec<DynSystemParam>| {
-                let _ = &library_handle;
-
                 let mut param_ptrs: Vec<*mut dyn_system_param> = Vec::new();
                 for param in params {
                     let boxed = Box::new(param);
@@ -94,7 +89,7 @@ where
                 let event_ptr = on.event() as *const E as *const u8;
 
                 unsafe {
-                    run_observer_fn(f_ptr as _, pointers_ptr, len, event_ptr as _);
+                    run_observer_fn(closure.as_ptr(), pointers_ptr, len, event_ptr as _);
                 };
             });
 
@@ -110,25 +105,22 @@ where
         &self,
         mut entity: EntityWorldMut,
         state: Box<SharedSystemState>,
-        f_ptr: usize,
+        closure: GuestClosure,
         run_observer_fn: RunObserverFn,
-        library_handle: LibraryHandle,
     ) {
         let observer_system = state.build_any_system(
             move |on: On<EntityEventWrapper<E>>, params: Vec<DynSystemParam>| {
-                let _ = &library_handle;
-
                 let mut param_ptrs: Vec<*mut dyn_system_param> = Vec::new();
                 for param in params {
                     param_ptrs.push(Box::into_raw(Box::new(param)) as *mut dyn_system_param);
                 }
 
                 let len = param_ptrs.len();
-                let params_ptr = param_ptrs.into_boxed_slice();
+                let pointers_ptr = param_ptrs.as_ptr();
 
                 let event_ptr = &on.event().inner as *const E as *const u8;
                 unsafe {
-                    run_observer_fn(f_ptr as _, params_ptr.as_ptr(), len, event_ptr as _);
+                    run_observer_fn(closure.as_ptr(), pointers_ptr, len, event_ptr as _);
                 };
             },
         );
@@ -144,6 +136,15 @@ where
 #[derive(Resource, Clone)]
 pub struct CurrentLibraryHandle(pub Option<LibraryHandle>);
 
+impl CurrentLibraryHandle {
+    /// Returns the handle of the guest library currently loading into `world`, if any.
+    pub fn get(world: &World) -> Option<LibraryHandle> {
+        world
+            .get_resource::<Self>()
+            .and_then(|handle| handle.0.clone())
+    }
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn bevy_system_state_build_on(
     world_ptr: *mut world,
@@ -152,10 +153,17 @@ pub unsafe extern "C" fn bevy_system_state_build_on(
     event_name_len: usize,
     f_ptr: *mut (),
     run_observer_fn: RunObserverFn,
+    drop_fn: DropClosureFn,
 ) -> bool {
     let world = unsafe { &mut *(world_ptr as *mut World) };
     let state: Box<SharedSystemState> = unsafe { Box::from_raw(state_ptr as _) };
 
+    // The closure is owned by the host from here, so it is also dropped on failure
+    let Some(closure) = (unsafe { GuestClosure::with_current_library(world, f_ptr, drop_fn) })
+    else {
+        return false;
+    };
+
     let event_name_bytes = unsafe { slice::from_raw_parts(event_name_ptr, event_name_len) };
     let event_name = match CStr::from_bytes_with_nul(event_name_bytes) {
         Ok(cstr) => match cstr.to_str() {
@@ -165,26 +173,13 @@ pub unsafe extern "C" fn bevy_system_state_build_on(
         Err(_) => return false,
     };
 
-    let Some(library_handle) = world
-        .get_resource::<CurrentLibraryHandle>()
-        .and_then(|h| h.0.clone())
-    else {
-        return false;
-    };
-
     let mut registry = match world.remove_resource::<SharedRegistry>() {
         Some(r) => r,
         None => return false,
     };
 
     if let Some(event_ops) = registry.events.remove(event_name) {
-        let observer_entity = event_ops.observe(
-            world,
-            state,
-            f_ptr as usize,
-            run_observer_fn,
-            library_handle,
-        );
+        let observer_entity = event_ops.observe(world, state, closure, run_observer_fn);
         registry.register_observer(observer_entity);
 
         let key = event_ops.type_path();
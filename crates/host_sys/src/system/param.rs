@@ -1,19 +1,164 @@
 use bevy::{
     ecs::{
+        component::Tick,
         prelude::*,
-        system::{DynParamBuilder, DynSystemParam, ParamBuilder, QueryParamBuilder},
-        world::{DeferredWorld, FilteredEntityMut, World},
+        query::{FilteredAccess, FilteredAccessSet},
+        system::{
+            DynParamBuilder, DynSystemParam, ParamBuilder, ParamSetBuilder, SystemChangeTick,
+            SystemMeta, SystemParam, SystemParamBuilder, SystemParamValidationError,
+        },
+        world::{unsafe_world_cell::UnsafeWorldCell, DeferredWorld, FilteredEntityMut, World},
     },
     prelude::*,
 };
 use bevy_mod_ffi_core::{
-    commands, deferred_world, dyn_system_param, param_builder, query, query_builder, system_state,
-    world, RunCommandFn,
+    commands, deferred_world, dyn_system_param, filtered_entity_mut, param_builder, param_set,
+    query, query_builder, system_state, world, RunCommandFn,
 };
+use std::{ptr, slice};
 
-use crate::SharedSystemState;
+use crate::{
+    query::{SharedQuery, SharedQueryState},
+    SharedSystemState,
+};
 
 type SharedQueryBuilder<'w> = QueryBuilder<'w, FilteredEntityMut<'static, 'static>>;
+type SharedQueryParam = Query<'static, 'static, FilteredEntityMut<'static, 'static>>;
+
+/// Each member of a shared param set is itself a list of params,
+/// so a guest member can be any combination of system params.
+type SharedParamSet<'w, 's> = ParamSet<'w, 's, Vec<Vec<DynSystemParam<'static, 'static>>>>;
+
+/// A [`SharedQuery`] param that skips its system when the query matches no entities,
+/// like [`Populated`].
+pub struct PopulatedSharedQuery<'w, 's>(pub SharedQuery<'w, 's>);
+
+// SAFETY: `SharedQuery` registers the same access as `Query` and builds its query from the same state.
+unsafe impl SystemParam for SharedQuery<'_, '_> {
+    type State = SharedQueryState;
+    type Item<'w, 's> = SharedQuery<'w, 's>;
+
+    fn init_state(world: &mut World) -> Self::State {
+        <SharedQueryParam as SystemParam>::init_state(world)
+    }
+
+    fn init_access(
+        state: &Self::State,
+        system_meta: &mut SystemMeta,
+        component_access_set: &mut FilteredAccessSet,
+        world: &mut World,
+    ) {
+        <SharedQueryParam as SystemParam>::init_access(
+            state,
+            system_meta,
+            component_access_set,
+            world,
+        );
+    }
+
+    unsafe fn get_param<'w, 's>(
+        state: &'s mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell<'w>,
+        change_tick: Tick,
+    ) -> Self::Item<'w, 's> {
+        let ticks = unsafe {
+            <SystemChangeTick as SystemParam>::get_param(&mut (), system_meta, world, change_tick)
+        };
+
+        state.update_archetypes_unsafe_world_cell(world);
+        unsafe { SharedQuery::new(world, state, ticks.last_run(), ticks.this_run()) }
+    }
+}
+
+// SAFETY: Delegates to `SharedQuery`, only adding the validation of `Populated`.
+unsafe impl SystemParam for PopulatedSharedQuery<'_, '_> {
+    type State = SharedQueryState;
+    type Item<'w, 's> = PopulatedSharedQuery<'w, 's>;
+
+    fn init_state(world: &mut World) -> Self::State {
+        <SharedQuery as SystemParam>::init_state(world)
+    }
+
+    fn init_access(
+        state: &Self::State,
+        system_meta: &mut SystemMeta,
+        component_access_set: &mut FilteredAccessSet,
+        world: &mut World,
+    ) {
+        <SharedQuery as SystemParam>::init_access(state, system_meta, component_access_set, world);
+    }
+
+    unsafe fn validate_param(
+        state: &mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell,
+    ) -> Result<(), SystemParamValidationError> {
+        unsafe {
+            <Populated<FilteredEntityMut<'static, 'static>> as SystemParam>::validate_param(
+                state,
+                system_meta,
+                world,
+            )
+        }
+    }
+
+    unsafe fn get_param<'w, 's>(
+        state: &'s mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell<'w>,
+        change_tick: Tick,
+    ) -> Self::Item<'w, 's> {
+        PopulatedSharedQuery(unsafe {
+            <SharedQuery as SystemParam>::get_param(state, system_meta, world, change_tick)
+        })
+    }
+}
+
+/// Builds the state of a [`SharedQuery`], [`Single`] or [`Populated`] from a guest query's access,
+/// since Bevy only provides dynamic builders for [`Query`].
+struct ValidatedQueryParamBuilder(FilteredAccess);
+
+impl ValidatedQueryParamBuilder {
+    fn build_state(self, world: &mut World) -> QueryState<FilteredEntityMut<'static, 'static>> {
+        let mut builder = SharedQueryBuilder::new(world);
+        builder.extend_access(self.0);
+        builder.build()
+    }
+}
+
+// SAFETY: `SharedQuery` uses the same `QueryState` as `Query`, built for the given `world`.
+unsafe impl<'w, 's> SystemParamBuilder<SharedQuery<'w, 's>> for ValidatedQueryParamBuilder {
+    fn build(self, world: &mut World) -> SharedQueryState {
+        self.build_state(world)
+    }
+}
+
+// SAFETY: `PopulatedSharedQuery` uses the same `QueryState` as `Query`, built for the given `world`.
+unsafe impl<'w, 's> SystemParamBuilder<PopulatedSharedQuery<'w, 's>>
+    for ValidatedQueryParamBuilder
+{
+    fn build(self, world: &mut World) -> SharedQueryState {
+        self.build_state(world)
+    }
+}
+
+// SAFETY: `Single` uses the same `QueryState` as `Query`, built for the given `world`.
+unsafe impl<'w, 's> SystemParamBuilder<Single<'w, 's, FilteredEntityMut<'static, 'static>>>
+    for ValidatedQueryParamBuilder
+{
+    fn build(self, world: &mut World) -> QueryState<FilteredEntityMut<'static, 'static>> {
+        self.build_state(world)
+    }
+}
+
+/// Takes ownership of a guest query builder and returns its access.
+fn take_query_access(query_builder_ptr: *mut query_builder) -> FilteredAccess {
+    let query_builder = unsafe { Box::from_raw(query_builder_ptr as *mut SharedQueryBuilder) };
+
+    // Clone the access before the query_builder is dropped, to avoid holding a reference to the world
+    query_builder.access().clone()
+}
 
 pub struct ParamBuilderAccumulator {
     pub builders: Vec<DynParamBuilder<'static>>,
@@ -38,17 +183,42 @@ pub unsafe extern "C" fn bevy_param_builder_add_query(
     query_builder_ptr: *mut query_builder,
 ) -> bool {
     let accumulator = unsafe { &mut *(builder_ptr as *mut ParamBuilderAccumulator) };
-    let query_builder = unsafe { Box::from_raw(query_builder_ptr as *mut SharedQueryBuilder) };
+    let access = take_query_access(query_builder_ptr);
 
-    // Clone the access before the query_builder is dropped, to avoid holding a reference to the world
-    let access = query_builder.access().clone();
-    drop(query_builder);
+    let dyn_builder = DynParamBuilder::new::<SharedQuery>(ValidatedQueryParamBuilder(access));
 
-    let dyn_builder = DynParamBuilder::new(QueryParamBuilder::new(
-        move |params: &mut SharedQueryBuilder| {
-            params.extend_access(access.clone());
-        },
-    ));
+    accumulator.builders.push(dyn_builder);
+
+    true
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_param_builder_add_single(
+    builder_ptr: *mut param_builder,
+    query_builder_ptr: *mut query_builder,
+) -> bool {
+    let accumulator = unsafe { &mut *(builder_ptr as *mut ParamBuilderAccumulator) };
+    let access = take_query_access(query_builder_ptr);
+
+    let dyn_builder = DynParamBuilder::new::<Single<FilteredEntityMut<'static, 'static>>>(
+        ValidatedQueryParamBuilder(access),
+    );
+
+    accumulator.builders.push(dyn_builder);
+
+    true
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_param_builder_add_populated(
+    builder_ptr: *mut param_builder,
+    query_builder_ptr: *mut query_builder,
+) -> bool {
+    let accumulator = unsafe { &mut *(builder_ptr as *mut ParamBuilderAccumulator) };
+    let access = take_query_access(query_builder_ptr);
+
+    let dyn_builder =
+        DynParamBuilder::new::<PopulatedSharedQuery>(ValidatedQueryParamBuilder(access));
 
     accumulator.builders.push(dyn_builder);
 
@@ -79,6 +249,30 @@ pub unsafe extern "C" fn bevy_param_builder_add_deferred_world(
     true
 }
 
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_param_builder_add_param_set(
+    builder_ptr: *mut param_builder,
+    members_ptr: *const *mut param_builder,
+    members_len: usize,
+) -> bool {
+    let accumulator = unsafe { &mut *(builder_ptr as *mut ParamBuilderAccumulator) };
+    let members = unsafe { slice::from_raw_parts(members_ptr, members_len) };
+
+    let member_builders: Vec<Vec<DynParamBuilder<'static>>> = members
+        .iter()
+        .map(|member_ptr| {
+            let member = unsafe { Box::from_raw(*member_ptr as *mut ParamBuilderAccumulator) };
+            member.builders
+        })
+        .collect();
+
+    let dyn_builder = DynParamBuilder::new::<SharedParamSet>(ParamSetBuilder(member_builders));
+
+    accumulator.builders.push(dyn_builder);
+
+    true
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn bevy_param_builder_build(
     world_ptr: *mut world,
@@ -112,13 +306,42 @@ pub unsafe extern "C" fn bevy_dyn_system_param_downcast_query(
     out_query: *mut *mut query,
 ) -> bool {
     let param = unsafe { Box::from_raw(param_ptr as *mut DynSystemParam) };
-    let query_param: Query<FilteredEntityMut> = param.downcast().unwrap();
+    let query_param: SharedQuery = param.downcast().unwrap();
     unsafe {
         *out_query = Box::into_raw(Box::new(query_param)) as *mut query;
     }
     true
 }
 
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_dyn_system_param_downcast_single(
+    param_ptr: *mut dyn_system_param,
+    out_entity: *mut u64,
+    out_entity_mut: *mut *mut filtered_entity_mut,
+) -> bool {
+    let param = unsafe { Box::from_raw(param_ptr as *mut DynSystemParam) };
+    let single_param: Single<FilteredEntityMut> = param.downcast().unwrap();
+    let entity_mut = single_param.into_inner();
+    unsafe {
+        *out_entity = entity_mut.id().to_bits();
+        *out_entity_mut = Box::into_raw(Box::new(entity_mut)) as *mut filtered_entity_mut;
+    }
+    true
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_dyn_system_param_downcast_populated(
+    param_ptr: *mut dyn_system_param,
+    out_query: *mut *mut query,
+) -> bool {
+    let param = unsafe { Box::from_raw(param_ptr as *mut DynSystemParam) };
+    let populated_param: PopulatedSharedQuery = param.downcast().unwrap();
+    unsafe {
+        *out_query = Box::into_raw(Box::new(populated_param.0)) as *mut query;
+    }
+    true
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn bevy_dyn_system_param_downcast_commands(
     param_ptr: *mut dyn_system_param,
@@ -145,6 +368,55 @@ pub unsafe extern "C" fn bevy_dyn_system_param_downcast_deferred_world(
     true
 }
 
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_dyn_system_param_downcast_param_set(
+    param_ptr: *mut dyn_system_param,
+    out_set: *mut *mut param_set,
+) -> bool {
+    let param = unsafe { Box::from_raw(param_ptr as *mut DynSystemParam) };
+    let set_param: SharedParamSet = param.downcast().unwrap();
+    unsafe {
+        *out_set = Box::into_raw(Box::new(set_param)) as *mut param_set;
+    }
+    true
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_param_set_get_mut(
+    set_ptr: *mut param_set,
+    index: usize,
+    out_params: *mut *mut *mut dyn_system_param,
+    out_params_len: *mut usize,
+) -> bool {
+    let set = unsafe { &mut *(set_ptr as *mut SharedParamSet) };
+
+    let params = set.get_mut(index);
+    let param_ptrs: Box<[*mut dyn_system_param]> = params
+        .into_iter()
+        .map(|param| Box::into_raw(Box::new(param)) as *mut dyn_system_param)
+        .collect();
+
+    unsafe {
+        *out_params_len = param_ptrs.len();
+        *out_params = Box::into_raw(param_ptrs) as *mut *mut dyn_system_param;
+    }
+
+    true
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_param_set_params_drop(
+    params_ptr: *mut *mut dyn_system_param,
+    params_len: usize,
+) {
+    let _ = unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(params_ptr, params_len)) };
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_param_set_drop(set_ptr: *mut param_set) {
+    let _ = unsafe { Box::from_raw(set_ptr as *mut SharedParamSet) };
+}
+
 struct SharedCommand {
     f_ptr: usize,
     run_command_fn: RunCommandFn,
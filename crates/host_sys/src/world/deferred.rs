@@ -2,9 +2,52 @@ use bevy::ecs::{
     component::ComponentId,
     entity::Entity,
     query::QueryState,
+    system::Commands,
     world::{DeferredWorld, FilteredEntityMut},
 };
-use bevy_mod_ffi_core::{deferred_world, query, query_state};
+use bevy_mod_ffi_core::{commands, deferred_world, query, query_state};
+
+use crate::query::SharedQuery;
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_deferred_world_get_component_id(
+    deferred_ptr: *mut deferred_world,
+    type_path_ptr: *const u8,
+    type_path_len: usize,
+    out_id: *mut usize,
+) -> bool {
+    let deferred = unsafe { &*(deferred_ptr as *const DeferredWorld) };
+
+    let Some(component_id) = super::component_id(deferred, type_path_ptr, type_path_len) else {
+        return false;
+    };
+
+    unsafe {
+        *out_id = component_id.index();
+    }
+
+    true
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_deferred_world_get_resource_id(
+    deferred_ptr: *mut deferred_world,
+    type_path_ptr: *const u8,
+    type_path_len: usize,
+    out_id: *mut usize,
+) -> bool {
+    let deferred = unsafe { &*(deferred_ptr as *const DeferredWorld) };
+
+    let Some(component_id) = super::resource_id(deferred, type_path_ptr, type_path_len) else {
+        return false;
+    };
+
+    unsafe {
+        *out_id = component_id.index();
+    }
+
+    true
+}
 
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn bevy_deferred_world_query(
@@ -16,7 +59,18 @@ pub unsafe extern "C" fn bevy_deferred_world_query(
     let query_state =
         unsafe { &mut *(query_state_ptr as *mut QueryState<FilteredEntityMut<'static, 'static>>) };
 
-    let query = deferred.query(query_state);
+    let world = deferred.as_unsafe_world_cell();
+    query_state.update_archetypes_unsafe_world_cell(world);
+
+    // SAFETY: A `DeferredWorld` may access every component.
+    let query = unsafe {
+        SharedQuery::new(
+            world,
+            query_state,
+            world.last_change_tick(),
+            world.change_tick(),
+        )
+    };
     unsafe {
         *out_query = Box::into_raw(Box::new(query)) as *mut query;
     }
@@ -24,6 +78,37 @@ pub unsafe extern "C" fn bevy_deferred_world_query(
     true
 }
 
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_deferred_world_contains_entity(
+    deferred_ptr: *mut deferred_world,
+    entity_bits: u64,
+) -> bool {
+    let deferred = unsafe { &*(deferred_ptr as *const DeferredWorld) };
+    deferred.get_entity(Entity::from_bits(entity_bits)).is_ok()
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_deferred_world_get(
+    deferred_ptr: *mut deferred_world,
+    entity_bits: u64,
+    component_id: usize,
+    out_ptr: *mut *const u8,
+) -> bool {
+    let deferred = unsafe { &*(deferred_ptr as *const DeferredWorld) };
+    let entity = Entity::from_bits(entity_bits);
+    let component_id = ComponentId::new(component_id);
+
+    let Some(ptr) = deferred.get_by_id(entity, component_id) else {
+        return false;
+    };
+
+    unsafe {
+        *out_ptr = ptr.as_ptr();
+    }
+
+    true
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn bevy_deferred_world_get_mut(
     deferred_ptr: *mut deferred_world,
@@ -46,6 +131,26 @@ pub unsafe extern "C" fn bevy_deferred_world_get_mut(
     true
 }
 
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_deferred_world_get_resource(
+    deferred_ptr: *mut deferred_world,
+    component_id: usize,
+    out_ptr: *mut *const u8,
+) -> bool {
+    let deferred = unsafe { &*(deferred_ptr as *const DeferredWorld) };
+    let component_id = ComponentId::new(component_id);
+
+    let Some(ptr) = deferred.get_resource_by_id(component_id) else {
+        return false;
+    };
+
+    unsafe {
+        *out_ptr = ptr.as_ptr();
+    }
+
+    true
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn bevy_deferred_world_get_resource_mut(
     deferred_ptr: *mut deferred_world,
@@ -66,6 +171,27 @@ pub unsafe extern "C" fn bevy_deferred_world_get_resource_mut(
     true
 }
 
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_deferred_world_commands(
+    deferred_ptr: *mut deferred_world,
+    out_commands: *mut *mut commands,
+) {
+    let deferred = unsafe { &mut *(deferred_ptr as *mut DeferredWorld) };
+    let commands: Commands = deferred.commands();
+
+    unsafe {
+        *out_commands = Box::into_raw(Box::new(commands)) as *mut commands;
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_deferred_world_reborrow(
+    deferred_ptr: *mut deferred_world,
+) -> *mut deferred_world {
+    let deferred = unsafe { &mut *(deferred_ptr as *mut DeferredWorld) };
+    Box::into_raw(Box::new(deferred.reborrow())) as *mut deferred_world
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn bevy_deferred_world_drop(deferred_ptr: *mut deferred_world) {
     let _ = unsafe { Box::from_raw(deferred_ptr as *mut DeferredWorld) };
@@ -1,15 +1,18 @@
-use crate::{system::observer::CurrentLibraryHandle, SharedRegistry, SharedSystemState};
+use crate::{world::OwnedBundle, GuestClosure, SharedRegistry, SharedSystemState};
 use bevy::ecs::{
     component::ComponentId,
-    world::{EntityWorldMut, FilteredEntityMut},
+    world::{EntityWorldMut, FilteredEntityMut, World},
+};
+use bevy_mod_ffi_core::{
+    entity_world_mut, filtered_entity_mut, system_state, world, BundleComponent, DropClosureFn,
+    RunCommandFn, RunObserverFn,
 };
-use bevy_mod_ffi_core::{entity_world_mut, filtered_entity_mut, system_state, RunObserverFn};
 use std::{ffi::CStr, slice};
 
 type SharedEntityRef = FilteredEntityMut<'static, 'static>;
 
 #[unsafe(no_mangle)]
-unsafe extern "C" fn bevy_world_entity_mut_drop(entity_ptr: *mut entity_world_mut) {
+pub unsafe extern "C" fn bevy_world_entity_mut_drop(entity_ptr: *mut entity_world_mut) {
     let _ = unsafe { Box::from_raw(entity_ptr as *mut EntityWorldMut) };
 }
 
@@ -68,11 +71,18 @@ pub unsafe extern "C" fn bevy_entity_world_mut_observe(
     event_name_len: usize,
     f_ptr: *mut (),
     run_observer_fn: RunObserverFn,
+    drop_fn: DropClosureFn,
 ) -> bool {
     let entity = unsafe { &mut *(entity_ptr as *mut EntityWorldMut) };
     let world = entity.world_mut();
     let state: Box<SharedSystemState> = unsafe { Box::from_raw(state_ptr as _) };
 
+    // The closure is owned by the host from here, so it is also dropped on failure
+    let Some(closure) = (unsafe { GuestClosure::with_current_library(world, f_ptr, drop_fn) })
+    else {
+        return false;
+    };
+
     let event_name_bytes = unsafe { slice::from_raw_parts(event_name_ptr, event_name_len) };
     let event_name = match CStr::from_bytes_with_nul(event_name_bytes) {
         Ok(cstr) => match cstr.to_str() {
@@ -82,13 +92,6 @@ pub unsafe extern "C" fn bevy_entity_world_mut_observe(
         Err(_) => return false,
     };
 
-    let Some(library_handle) = world
-        .get_resource::<CurrentLibraryHandle>()
-        .and_then(|h| h.0.clone())
-    else {
-        return false;
-    };
-
     let mut registry = match world.remove_resource::<SharedRegistry>() {
         Some(r) => r,
         None => return false,
@@ -96,13 +99,7 @@ pub unsafe extern "C" fn bevy_entity_world_mut_observe(
 
     if let Some(event_ops) = registry.events.remove(event_name) {
         entity.reborrow_scope(|entity| {
-            event_ops.observe_entity(
-                entity,
-                state,
-                f_ptr as usize,
-                run_observer_fn,
-                library_handle,
-            );
+            event_ops.observe_entity(entity, state, closure, run_observer_fn);
         });
 
         registry.register_observer(entity.id());
@@ -161,3 +158,46 @@ pub unsafe extern "C" fn bevy_entity_world_mut_trigger(
         false
     }
 }
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_entity_world_mut_insert(
+    entity_ptr: *mut entity_world_mut,
+    components_ptr: *const BundleComponent,
+    component_len: usize,
+) -> bool {
+    let entity = unsafe { &mut *(entity_ptr as *mut EntityWorldMut) };
+    let components = unsafe { slice::from_raw_parts(components_ptr, component_len) };
+
+    // Required components are only constructed if the entity doesn't have them yet
+    let bundle =
+        unsafe { OwnedBundle::new(entity.world(), components, |id| entity.contains_id(id)) };
+    bundle.insert(entity);
+
+    true
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_entity_world_mut_remove(
+    entity_ptr: *mut entity_world_mut,
+    component_id: usize,
+) -> bool {
+    let entity = unsafe { &mut *(entity_ptr as *mut EntityWorldMut) };
+    let component_id = ComponentId::new(component_id);
+
+    if entity.world().components().get_info(component_id).is_none() {
+        return false;
+    }
+
+    entity.remove_by_id(component_id);
+    true
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_entity_world_mut_world_scope(
+    entity_ptr: *mut entity_world_mut,
+    f_ptr: *mut (),
+    run_fn: RunCommandFn,
+) {
+    let entity = unsafe { &mut *(entity_ptr as *mut EntityWorldMut) };
+    entity.world_scope(|world| unsafe { run_fn(f_ptr, world as *mut World as *mut world) });
+}
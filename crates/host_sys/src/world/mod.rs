@@ -1,18 +1,26 @@
-use crate::{DynamicHooks, SharedRegistry, SharedSystem, SystemIn};
+use crate::{
+    component::{self, CloneHooks, ComponentDrop, ComponentObserverHook},
+    system::CurrentLibraryHandle,
+    DynamicHooks, RequiredComponent, SharedRegistry, SharedSystem, SystemIn,
+};
 use bevy::{
     ecs::{
-        component::{ComponentCloneBehavior, ComponentDescriptor, ComponentId, StorageType},
+        change_detection::MaybeLocation,
+        component::{ComponentDescriptor, ComponentId, StorageType},
         lifecycle::HookContext,
+        system::RunSystemError,
         world::{DeferredWorld, World},
     },
     prelude::*,
     ptr::OwningPtr,
 };
 use bevy_mod_ffi_core::{
-    deferred_world, entity_world_mut, system, world, BundleComponent, ComponentHookFn,
+    deferred_world, entity_world_mut, system, world, BundleComponent, ComponentCloneFn,
+    ComponentDropFn, ComponentHookFn, ComponentHookKind, ComponentObserverFn, MapEntitiesFn,
+    RegisterComponentResult, RequiredConstructorFn, ResourceScopeResult, RunResourceScopeFn,
 };
 use std::{
-    alloc::{self, Layout},
+    alloc::Layout,
     any::TypeId,
     ffi::CStr,
     ptr::{self, NonNull},
@@ -24,11 +32,11 @@ fn run_guest_hook(
     deferred: &mut DeferredWorld<'_>,
     context: &HookContext,
 ) {
-    let deferred_ptr = deferred as *mut DeferredWorld as *mut deferred_world;
-    let entity_bits = context.entity.to_bits();
-    let component_id_idx = context.component_id.index();
+    // The guest takes ownership of the boxed `DeferredWorld` and drops it when the hook returns
+    let deferred_ptr = Box::into_raw(Box::new(deferred.reborrow())) as *mut deferred_world;
+    let hook_context = component::guest_hook_context(context);
     unsafe {
-        hook_fn(deferred_ptr, entity_bits, component_id_idx);
+        hook_fn(deferred_ptr, &hook_context);
     }
 }
 
@@ -45,10 +53,7 @@ pub unsafe extern "C" fn bevy_world_get_resource_id(
 ) -> bool {
     let world = unsafe { &*(world_ptr as *const World) };
 
-    let Some(type_id) = get_type_id(type_path_ptr, type_path_len, world) else {
-        return false;
-    };
-    let Some(component_id) = world.components().get_resource_id(type_id) else {
+    let Some(component_id) = resource_id(world, type_path_ptr, type_path_len) else {
         return false;
     };
 
@@ -59,6 +64,15 @@ pub unsafe extern "C" fn bevy_world_get_resource_id(
     true
 }
 
+pub(crate) fn resource_id(
+    world: &World,
+    type_path_ptr: *const u8,
+    type_path_len: usize,
+) -> Option<ComponentId> {
+    let type_id = get_type_id(type_path_ptr, type_path_len, world)?;
+    world.components().get_resource_id(type_id)
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn bevy_world_get_resource(
     world_ptr: *mut world,
@@ -82,6 +96,90 @@ pub unsafe extern "C" fn bevy_world_get_resource(
     true
 }
 
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_world_get_resource_mut(
+    world_ptr: *mut world,
+    component_id: usize,
+    out_ptr: *mut *mut u8,
+) -> bool {
+    let world = unsafe { &mut *(world_ptr as *mut World) };
+    let id = ComponentId::new(component_id);
+
+    let Some(mut mut_untyped) = world.get_resource_mut_by_id(id) else {
+        return false;
+    };
+
+    unsafe {
+        *out_ptr = mut_untyped.as_mut().as_ptr();
+    }
+
+    true
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_world_contains_resource(
+    world_ptr: *mut world,
+    component_id: usize,
+) -> bool {
+    let world = unsafe { &*(world_ptr as *const World) };
+    world.contains_resource_by_id(ComponentId::new(component_id))
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_world_resource_scope(
+    world_ptr: *mut world,
+    component_id: usize,
+    f_ptr: *mut (),
+    run_scope_fn: RunResourceScopeFn,
+) -> ResourceScopeResult {
+    let world = unsafe { &mut *(world_ptr as *mut World) };
+    let id = ComponentId::new(component_id);
+
+    let Some(ticks) = world.get_resource_change_ticks_by_id(id) else {
+        return ResourceScopeResult::NotFound;
+    };
+    let Some(info) = world.components().get_info(id) else {
+        return ResourceScopeResult::NotFound;
+    };
+    // The resource is moved out by copying its bytes, so it can't need dropping
+    if info.drop().is_some() {
+        return ResourceScopeResult::NeedsDrop;
+    }
+    let layout = info.layout();
+
+    let Some(resource_ptr) = world.get_resource_by_id(id) else {
+        return ResourceScopeResult::NotFound;
+    };
+    let original = unsafe { slice::from_raw_parts(resource_ptr.as_ptr(), layout.size()) }.to_vec();
+    let buffer = unsafe { component::alloc_component(layout) };
+    unsafe {
+        ptr::copy_nonoverlapping(original.as_ptr(), buffer, layout.size());
+    }
+    world.remove_resource_by_id(id);
+
+    unsafe {
+        run_scope_fn(f_ptr, world_ptr, buffer);
+    }
+
+    let changed = unsafe { slice::from_raw_parts(buffer, layout.size()) } != original;
+    unsafe {
+        let owning_ptr = OwningPtr::new(NonNull::new_unchecked(buffer));
+        world.insert_resource_by_id(id, owning_ptr, MaybeLocation::caller());
+        component::dealloc_component(buffer, layout);
+    }
+
+    // Reinserting the resource marks it as added, so restore the ticks it had before the scope
+    let mut resource = world.get_resource_mut_by_id(id).unwrap();
+    resource.set_last_added(ticks.added);
+    if changed {
+        resource.set_changed();
+    } else {
+        resource.set_last_changed(ticks.changed);
+    }
+
+    ResourceScopeResult::Ran
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn bevy_world_get_component_id(
     world_ptr: *mut world,
@@ -89,30 +187,150 @@ pub unsafe extern "C" fn bevy_world_get_component_id(
     type_path_len: usize,
     out_id: *mut usize,
 ) -> bool {
-    let world = unsafe { &mut *(world_ptr as *mut World) };
+    let world = unsafe { &*(world_ptr as *const World) };
+
+    let Some(component_id) = component_id(world, type_path_ptr, type_path_len) else {
+        return false;
+    };
+
+    unsafe {
+        *out_id = component_id.index();
+    }
 
+    true
+}
+
+pub(crate) fn component_id(
+    world: &World,
+    type_path_ptr: *const u8,
+    type_path_len: usize,
+) -> Option<ComponentId> {
     let type_path_bytes = unsafe { slice::from_raw_parts(type_path_ptr, type_path_len) };
     let type_path = CStr::from_bytes_with_nul(type_path_bytes)
         .unwrap()
         .to_str()
         .unwrap();
 
-    let component_id = if let Some(type_id) = get_type_id(type_path_ptr, type_path_len, world) {
-        let Some(component_id) = world.components().get_id(type_id) else {
-            return false;
-        };
-        component_id
-    } else if let Some(registry) = world.get_resource::<SharedRegistry>() {
-        let Some(component_id) = registry.get_component_id(type_path) else {
-            return false;
-        };
-        component_id
+    if let Some(type_id) = get_type_id(type_path_ptr, type_path_len, world) {
+        world.components().get_id(type_id)
     } else {
+        world
+            .get_resource::<SharedRegistry>()?
+            .get_component_id(type_path)
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_world_get(
+    world_ptr: *mut world,
+    entity_bits: u64,
+    component_id: usize,
+    out_ptr: *mut *const u8,
+) -> bool {
+    let world = unsafe { &*(world_ptr as *const World) };
+    let entity = Entity::from_bits(entity_bits);
+
+    let Some(ptr) = world.get_by_id(entity, ComponentId::new(component_id)) else {
         return false;
     };
 
     unsafe {
-        *out_id = component_id.index();
+        *out_ptr = ptr.as_ptr();
+    }
+
+    true
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_world_get_mut(
+    world_ptr: *mut world,
+    entity_bits: u64,
+    component_id: usize,
+    out_ptr: *mut *mut u8,
+) -> bool {
+    let world = unsafe { &mut *(world_ptr as *mut World) };
+    let entity = Entity::from_bits(entity_bits);
+
+    let Some(mut mut_untyped) = world.get_mut_by_id(entity, ComponentId::new(component_id)) else {
+        return false;
+    };
+
+    unsafe {
+        *out_ptr = mut_untyped.as_mut().as_ptr();
+    }
+
+    true
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_world_despawn(world_ptr: *mut world, entity_bits: u64) -> bool {
+    let world = unsafe { &mut *(world_ptr as *mut World) };
+    world.despawn(Entity::from_bits(entity_bits))
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_world_entities_len(world_ptr: *mut world) -> u32 {
+    let world = unsafe { &*(world_ptr as *const World) };
+    world.entities().len()
+}
+
+/// Fills `out_entities` with up to `capacity` entities, in archetype order.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_world_iter_entities(
+    world_ptr: *mut world,
+    out_entities: *mut u64,
+    capacity: usize,
+    out_len: *mut usize,
+) {
+    let world = unsafe { &*(world_ptr as *const World) };
+    let entities = unsafe { slice::from_raw_parts_mut(out_entities, capacity) };
+
+    let ids = world
+        .archetypes()
+        .iter()
+        .flat_map(|archetype| archetype.entities())
+        .map(|entity| entity.id().to_bits());
+
+    let mut len = 0;
+    for (out, id) in entities.iter_mut().zip(ids) {
+        *out = id;
+        len += 1;
+    }
+
+    unsafe {
+        *out_len = len;
+    }
+}
+
+/// Fills `out_entities` with up to `capacity` children of `entity`,
+/// or returns `false` if it doesn't exist or has no children.
+///
+/// `out_len` is set to the number of children, so a larger buffer can be passed if it didn't fit.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_world_entity_children(
+    world_ptr: *mut world,
+    entity_bits: u64,
+    out_entities: *mut u64,
+    capacity: usize,
+    out_len: *mut usize,
+) -> bool {
+    let world = unsafe { &*(world_ptr as *const World) };
+    let Some(children) = world
+        .get_entity(Entity::from_bits(entity_bits))
+        .ok()
+        .and_then(|entity| entity.get::<Children>())
+    else {
+        return false;
+    };
+
+    // `Children` is owned by the host, so its entities are copied out instead of shared
+    let entities = unsafe { slice::from_raw_parts_mut(out_entities, capacity) };
+    for (out, child) in entities.iter_mut().zip(children.iter()) {
+        *out = child.to_bits();
+    }
+
+    unsafe {
+        *out_len = children.len();
     }
 
     true
@@ -124,7 +342,7 @@ pub unsafe extern "C" fn bevy_world_run_system(
     system_ptr: *mut system,
     input_ptr: *mut u8,
     output_ptr: *mut u8,
-) {
+) -> bool {
     let world = unsafe { &mut *(world_ptr as *mut World) };
     let system = unsafe { &mut *(system_ptr as *mut SharedSystem) };
 
@@ -132,7 +350,12 @@ pub unsafe extern "C" fn bevy_world_run_system(
         input_ptr,
         output_ptr,
     };
-    system.run(input, world).unwrap();
+    match system.run(input, world) {
+        Ok(()) => true,
+        // Params like `Single` skip the system when validation fails, leaving the output unwritten
+        Err(RunSystemError::Skipped(_)) => false,
+        Err(error) => panic!("Failed to run system: {error}"),
+    }
 }
 
 #[unsafe(no_mangle)]
@@ -143,13 +366,19 @@ pub unsafe extern "C" fn bevy_world_register_component(
     size: usize,
     align: usize,
     is_table: u8,
+    is_mutable: bool,
+    drop: Option<ComponentDropFn>,
     on_add: Option<ComponentHookFn>,
     on_insert: Option<ComponentHookFn>,
     on_replace: Option<ComponentHookFn>,
     on_remove: Option<ComponentHookFn>,
     on_despawn: Option<ComponentHookFn>,
+    clone_ignore: bool,
+    clone_fn: Option<ComponentCloneFn>,
+    clone_data: *const (),
+    map_entities: Option<MapEntitiesFn>,
     out_id: *mut usize,
-) -> bool {
+) -> RegisterComponentResult {
     let world = unsafe { &mut *(world_ptr as *mut World) };
 
     let name_bytes = unsafe { slice::from_raw_parts(name_ptr, name_len) };
@@ -161,7 +390,7 @@ pub unsafe extern "C" fn bevy_world_register_component(
 
     let layout = match Layout::from_size_align(size, align) {
         Ok(l) => l,
-        Err(_) => return false,
+        Err(_) => return RegisterComponentResult::InvalidLayout,
     };
 
     let storage_type = if is_table != 0 {
@@ -170,14 +399,22 @@ pub unsafe extern "C" fn bevy_world_register_component(
         StorageType::SparseSet
     };
 
+    let (drop, component_drop) = match drop {
+        Some(drop_fn) => match guest_drop_fn(world, drop_fn) {
+            Some((drop, component_drop)) => (Some(drop), Some(component_drop)),
+            None => return RegisterComponentResult::NoDropSlots,
+        },
+        None => (None, None),
+    };
+
     let descriptor = unsafe {
         ComponentDescriptor::new_with_layout(
             name.clone(),
             storage_type,
             layout,
-            None,
-            true,
-            ComponentCloneBehavior::Ignore,
+            drop,
+            is_mutable,
+            component::clone_behavior(clone_ignore),
         )
     };
 
@@ -195,6 +432,17 @@ pub unsafe extern "C" fn bevy_world_register_component(
         let mut registry = world.resource_mut::<SharedRegistry>();
         registry.type_path_to_id.insert(name, id);
         registry.hooks.insert(id, dynamic_hooks);
+        if let Some(component_drop) = component_drop {
+            registry.drops.insert(id, component_drop);
+        }
+        registry.clone_hooks.insert(
+            id,
+            CloneHooks {
+                clone_fn,
+                clone_data,
+                map_entities,
+            },
+        );
     }
 
     if let Some(hooks) = world.register_component_hooks_by_id(id) {
@@ -264,9 +512,192 @@ pub unsafe extern "C" fn bevy_world_register_component(
         *out_id = id.index();
     }
 
+    RegisterComponentResult::Registered
+}
+
+/// Returns a drop function for a guest component, releasing the drop functions of
+/// unloaded components whose values are gone if every slot is taken.
+fn guest_drop_fn(
+    world: &mut World,
+    drop_fn: ComponentDropFn,
+) -> Option<(unsafe fn(OwningPtr<'_>), ComponentDrop)> {
+    let library_handle = CurrentLibraryHandle::get(world);
+    let library_id = world.resource::<SharedRegistry>().current_library_id();
+    if let Some(drop) = component::guest_drop_fn(drop_fn, library_handle.clone(), library_id) {
+        return Some(drop);
+    }
+
+    world.resource_scope(|world, mut registry: Mut<SharedRegistry>| {
+        registry.release_component_drops(world);
+    });
+    component::guest_drop_fn(drop_fn, library_handle, library_id)
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_world_register_required_component(
+    world_ptr: *mut world,
+    requiree: usize,
+    required: usize,
+    constructor: RequiredConstructorFn,
+    constructor_data: *const (),
+) -> bool {
+    let world = unsafe { &mut *(world_ptr as *mut World) };
+    let requiree = ComponentId::new(requiree);
+    let required = ComponentId::new(required);
+
+    if world.components().get_info(requiree).is_none()
+        || world.components().get_info(required).is_none()
+    {
+        return false;
+    }
+
+    let mut registry = world.resource_mut::<SharedRegistry>();
+    let Some(library_id) = registry.current_library_id() else {
+        return false;
+    };
+    let direct = registry.required.entry(requiree).or_default();
+    if direct.iter().any(|r| r.component_id == required) {
+        return false;
+    }
+    direct.push(RequiredComponent {
+        component_id: required,
+        constructor,
+        constructor_data,
+        library_id,
+    });
+
+    // Guest bundles are built with their required components, but the host inserts the requiree
+    // on its own, so an observer inserts the rest
+    let spawn_required_observer = registry.spawn_required_observer;
+    let observer_entity = spawn_required_observer(world, requiree, required);
+    world
+        .resource_mut::<SharedRegistry>()
+        .register_observer(observer_entity);
+
+    true
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_world_add_component_hook(
+    world_ptr: *mut world,
+    component_id: usize,
+    kind: ComponentHookKind,
+    hook_fn: ComponentObserverFn,
+    hook_data: *const (),
+) -> bool {
+    let world = unsafe { &mut *(world_ptr as *mut World) };
+    let component_id = ComponentId::new(component_id);
+
+    if world.components().get_info(component_id).is_none() {
+        return false;
+    }
+
+    let Some(library_handle) = CurrentLibraryHandle::get(world) else {
+        return false;
+    };
+
+    let hook = ComponentObserverHook {
+        hook_fn,
+        hook_data,
+        library_handle,
+    };
+    let spawn_hook_observer = world.resource::<SharedRegistry>().spawn_hook_observer;
+    let observer_entity = spawn_hook_observer(world, component_id, kind, hook);
+    world
+        .resource_mut::<SharedRegistry>()
+        .register_observer(observer_entity);
+
     true
 }
 
+/// The components of a guest bundle and the components they require, moved into one host buffer.
+///
+/// The buffer is freed on drop, and components that were never inserted are forgotten.
+pub(crate) struct OwnedBundle {
+    ids: Vec<ComponentId>,
+    offsets: Vec<usize>,
+    buffer: *mut u8,
+    layout: Layout,
+}
+
+impl OwnedBundle {
+    /// Copies `components` into a new buffer, followed by the components they require.
+    ///
+    /// Required components for which `skip_required` returns `true` are not constructed.
+    ///
+    /// # Safety
+    /// Each component pointer must be valid for reads of its component's layout.
+    pub(crate) unsafe fn new(
+        world: &World,
+        components: &[BundleComponent],
+        skip_required: impl Fn(ComponentId) -> bool,
+    ) -> Self {
+        let mut ids: Vec<_> = components
+            .iter()
+            .map(|component| ComponentId::new(component.component_id))
+            .collect();
+        let required: Vec<_> = world
+            .resource::<SharedRegistry>()
+            .required_components(&ids)
+            .into_iter()
+            .filter(|required| !skip_required(required.component_id))
+            .collect();
+        ids.extend(required.iter().map(|required| required.component_id));
+
+        let mut layout = Layout::new::<()>();
+        let mut offsets = Vec::with_capacity(ids.len());
+        let mut sizes = Vec::with_capacity(ids.len());
+        for id in &ids {
+            let component_layout = world.components().get_info(*id).unwrap().layout();
+            let (extended, offset) = layout.extend(component_layout).unwrap();
+            layout = extended;
+            offsets.push(offset);
+            sizes.push(component_layout.size());
+        }
+
+        // Zero-sized components get a dangling pointer aligned for the whole bundle
+        let buffer = unsafe { component::alloc_component(layout) };
+        for ((component, offset), size) in components.iter().zip(&offsets).zip(&sizes) {
+            unsafe {
+                ptr::copy_nonoverlapping(component.ptr, buffer.add(*offset), *size);
+            }
+        }
+        for (required, offset) in required.iter().zip(&offsets[components.len()..]) {
+            unsafe {
+                (required.constructor)(required.constructor_data, buffer.add(*offset));
+            }
+        }
+
+        Self {
+            ids,
+            offsets,
+            buffer,
+            layout,
+        }
+    }
+
+    /// Moves every component into `entity` at once.
+    pub(crate) fn insert(self, entity: &mut EntityWorldMut) {
+        if self.ids.is_empty() {
+            return;
+        }
+
+        unsafe {
+            let owning_ptrs = self
+                .offsets
+                .iter()
+                .map(|offset| OwningPtr::new(NonNull::new_unchecked(self.buffer.add(*offset))));
+            entity.insert_by_ids(&self.ids, owning_ptrs);
+        }
+    }
+}
+
+impl Drop for OwnedBundle {
+    fn drop(&mut self) {
+        unsafe { component::dealloc_component(self.buffer, self.layout) };
+    }
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn bevy_world_spawn(
     world_ptr: *mut world,
@@ -277,32 +708,92 @@ pub unsafe extern "C" fn bevy_world_spawn(
 ) -> bool {
     let world = unsafe { &mut *(world_ptr as *mut World) };
     let components = unsafe { slice::from_raw_parts(components_ptr, component_len) };
+    let bundle = unsafe { OwnedBundle::new(world, components, |_| false) };
 
-    let mut components_data = Vec::new();
-    for component in components {
-        let component_id = ComponentId::new(component.component_id);
-        let component_info = world.components().get_info(component_id).unwrap();
-        let layout = component_info.layout();
+    let mut entity = world.spawn_empty();
+    bundle.insert(&mut entity);
 
-        let buffer = alloc::alloc(layout);
-        unsafe {
-            ptr::copy(component.ptr, buffer, layout.size());
-        }
+    unsafe {
+        *out_entity = entity.id().to_bits();
+        *out_entity_world_mut_ptr = Box::into_raw(Box::new(entity)) as *mut entity_world_mut;
+    }
+
+    true
+}
 
-        components_data.push((component_id, buffer));
+/// Spawns `count` entities from `data`, which holds the components of each entity back to back
+/// in the order of `component_ids`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn bevy_world_spawn_batch(
+    world_ptr: *mut world,
+    component_ids_ptr: *const usize,
+    component_len: usize,
+    data_ptr: *const u8,
+    data_len: usize,
+    count: usize,
+    out_entities: *mut u64,
+) -> bool {
+    let world = unsafe { &mut *(world_ptr as *mut World) };
+    let component_ids = unsafe { slice::from_raw_parts(component_ids_ptr, component_len) };
+
+    let mut ids = Vec::new();
+    let mut layouts = Vec::new();
+    for component_id in component_ids {
+        let component_id = ComponentId::new(*component_id);
+        let Some(component_info) = world.components().get_info(component_id) else {
+            return false;
+        };
+        ids.push(component_id);
+        layouts.push(component_info.layout());
     }
 
-    let mut entity = world.spawn_empty();
-    for (component_id, buffer) in components_data {
+    let stride: usize = layouts.iter().map(Layout::size).sum();
+    if stride * count != data_len {
+        return false;
+    }
+
+    let required = world.resource::<SharedRegistry>().required_components(&ids);
+    for required in &required {
+        let component_info = world.components().get_info(required.component_id).unwrap();
+        ids.push(required.component_id);
+        layouts.push(component_info.layout());
+    }
+
+    // Every entity is built in the same scratch buffers, as inserting moves out of them
+    let buffers: Vec<_> = layouts
+        .iter()
+        .map(|layout| unsafe { component::alloc_component(*layout) })
+        .collect();
+
+    for index in 0..count {
+        let mut offset = index * stride;
+        for (buffer, layout) in buffers.iter().zip(&layouts).take(component_len) {
+            unsafe {
+                ptr::copy_nonoverlapping(data_ptr.add(offset), *buffer, layout.size());
+            }
+            offset += layout.size();
+        }
+
+        for (required, buffer) in required.iter().zip(&buffers[component_len..]) {
+            unsafe {
+                (required.constructor)(required.constructor_data, *buffer);
+            }
+        }
+
+        // Bevy only spawns typed bundles straight into their archetype, so each entity is moved
+        // from the empty archetype once, along the edge Bevy caches for the batch's components
+        let mut entity = world.spawn_empty();
         unsafe {
-            let owning_ptr = OwningPtr::new(NonNull::new_unchecked(buffer));
-            entity.insert_by_id(component_id, owning_ptr);
+            let owning_ptrs = buffers
+                .iter()
+                .map(|buffer| OwningPtr::new(NonNull::new_unchecked(*buffer)));
+            entity.insert_by_ids(&ids, owning_ptrs);
+            *out_entities.add(index) = entity.id().to_bits();
         }
     }
 
-    unsafe {
-        *out_entity = entity.id().to_bits();
-        *out_entity_world_mut_ptr = Box::into_raw(Box::new(entity)) as *mut entity_world_mut;
+    for (buffer, layout) in buffers.into_iter().zip(layouts) {
+        unsafe { component::dealloc_component(buffer, layout) };
     }
 
     true
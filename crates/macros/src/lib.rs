@@ -1,6 +1,10 @@
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{DeriveInput, Expr, Token, parse_macro_input};
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{
+    Data, DeriveInput, Expr, Fields, Ident, Lifetime, Member, Path, Token, Type, parse_macro_input,
+    punctuated::Punctuated, visit_mut::VisitMut,
+};
 
 #[proc_macro_attribute]
 pub fn main(input: TokenStream, attrs: TokenStream) -> TokenStream {
@@ -20,20 +24,84 @@ pub fn main(input: TokenStream, attrs: TokenStream) -> TokenStream {
     .into()
 }
 
-#[proc_macro_derive(SharedComponent, attributes(component))]
+#[proc_macro_derive(
+    SharedComponent,
+    attributes(component, require, entities, relationship, relationship_target)
+)]
 pub fn derive_shared_component(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
 
     let mut storage_type = quote! { bevy_mod_ffi::component::StorageType::Table };
     let mut mutability = quote! { bevy_mod_ffi::component::Mutable };
-    let mut on_add = quote! { None };
-    let mut on_insert = quote! { None };
-    let mut on_replace = quote! { None };
-    let mut on_remove = quote! { None };
-    let mut on_despawn = quote! { None };
+    let mut on_add = None;
+    let mut on_insert = None;
+    let mut on_replace = None;
+    let mut on_remove = None;
+    let mut on_despawn = None;
+    let mut clone_behavior = None;
+    let mut required = Vec::new();
+    let mut relationship = None;
+    let mut relationship_target = None;
 
     for attr in &input.attrs {
+        if attr.path().is_ident("relationship") {
+            let mut target = None;
+            let result = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("relationship_target") {
+                    target = Some(meta.value()?.parse::<Type>()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `relationship_target = ...`"))
+                }
+            });
+            if let Err(error) = result {
+                return error.to_compile_error().into();
+            }
+            let Some(target) = target else {
+                return syn::Error::new_spanned(attr, "missing `relationship_target = ...`")
+                    .to_compile_error()
+                    .into();
+            };
+            relationship = Some(target);
+            continue;
+        }
+
+        if attr.path().is_ident("relationship_target") {
+            let mut source = None;
+            let mut linked_spawn = false;
+            let result = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("relationship") {
+                    source = Some(meta.value()?.parse::<Type>()?);
+                    Ok(())
+                } else if meta.path.is_ident("linked_spawn") {
+                    linked_spawn = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `relationship = ...` or `linked_spawn`"))
+                }
+            });
+            if let Err(error) = result {
+                return error.to_compile_error().into();
+            }
+            let Some(source) = source else {
+                return syn::Error::new_spanned(attr, "missing `relationship = ...`")
+                    .to_compile_error()
+                    .into();
+            };
+            relationship_target = Some((source, linked_spawn));
+            continue;
+        }
+
+        if attr.path().is_ident("require") {
+            let parser = Punctuated::<RequiredComponent, Token![,]>::parse_terminated;
+            match attr.parse_args_with(parser) {
+                Ok(items) => required.extend(items),
+                Err(error) => return error.to_compile_error().into(),
+            }
+            continue;
+        }
+
         if !attr.path().is_ident("component") {
             continue;
         }
@@ -56,28 +124,180 @@ pub fn derive_shared_component(input: TokenStream) -> TokenStream {
             } else if meta.path.is_ident("on_add") {
                 let _: Token![=] = meta.input.parse()?;
                 let func: Expr = meta.input.parse()?;
-                on_add = quote! { Some(#func) };
+                on_add = Some(quote! { Some(#func) });
             } else if meta.path.is_ident("on_insert") {
                 let _: Token![=] = meta.input.parse()?;
                 let func: Expr = meta.input.parse()?;
-                on_insert = quote! { Some(#func) };
+                on_insert = Some(quote! { Some(#func) });
             } else if meta.path.is_ident("on_replace") {
                 let _: Token![=] = meta.input.parse()?;
                 let func: Expr = meta.input.parse()?;
-                on_replace = quote! { Some(#func) };
+                on_replace = Some(quote! { Some(#func) });
             } else if meta.path.is_ident("on_remove") {
                 let _: Token![=] = meta.input.parse()?;
                 let func: Expr = meta.input.parse()?;
-                on_remove = quote! { Some(#func) };
+                on_remove = Some(quote! { Some(#func) });
             } else if meta.path.is_ident("on_despawn") {
                 let _: Token![=] = meta.input.parse()?;
                 let func: Expr = meta.input.parse()?;
-                on_despawn = quote! { Some(#func) };
+                on_despawn = Some(quote! { Some(#func) });
+            } else if meta.path.is_ident("clone_behavior") {
+                let _: Token![=] = meta.input.parse()?;
+                let behavior: Expr = meta.input.parse()?;
+                clone_behavior = Some(quote! {
+                    fn clone_behavior() -> bevy_mod_ffi::component::ComponentCloneBehavior {
+                        bevy_mod_ffi::component::ComponentCloneBehavior::#behavior
+                    }
+                });
             }
             Ok(())
         });
     }
 
+    let mut relationship_impl = None;
+    if let Some(target) = &relationship {
+        if relationship_target.is_some() {
+            return syn::Error::new_spanned(
+                name,
+                "a component can't be both a relationship and a relationship target",
+            )
+            .to_compile_error()
+            .into();
+        }
+        if on_insert.is_some() || on_replace.is_some() {
+            return syn::Error::new_spanned(
+                name,
+                "relationship components can't set custom `on_insert` or `on_replace` hooks",
+            )
+            .to_compile_error()
+            .into();
+        }
+
+        let (field, others) = match relationship_field(&input) {
+            Ok(fields) => fields,
+            Err(error) => return error.to_compile_error().into(),
+        };
+        // Relationships are immutable so their targets can't silently go out of sync
+        mutability = quote! { bevy_mod_ffi::component::Immutable };
+        on_insert = Some(quote! {
+            Some(<Self as bevy_mod_ffi::relationship::Relationship>::on_insert)
+        });
+        on_replace = Some(quote! {
+            Some(<Self as bevy_mod_ffi::relationship::Relationship>::on_replace)
+        });
+        relationship_impl = Some(quote! {
+            impl bevy_mod_ffi::relationship::Relationship for #name {
+                type RelationshipTarget = #target;
+
+                fn get(&self) -> bevy_mod_ffi::prelude::Entity {
+                    self.#field
+                }
+
+                fn from(entity: bevy_mod_ffi::prelude::Entity) -> Self {
+                    Self {
+                        #field: entity,
+                        #(#others: Default::default(),)*
+                    }
+                }
+            }
+        });
+    }
+
+    if let Some((source, linked_spawn)) = &relationship_target {
+        if on_replace.is_some() || (*linked_spawn && on_despawn.is_some()) {
+            return syn::Error::new_spanned(
+                name,
+                "relationship targets can't set custom `on_replace` or `on_despawn` hooks",
+            )
+            .to_compile_error()
+            .into();
+        }
+
+        let (field, others) = match relationship_field(&input) {
+            Ok(fields) => fields,
+            Err(error) => return error.to_compile_error().into(),
+        };
+        on_replace = Some(quote! {
+            Some(<Self as bevy_mod_ffi::relationship::RelationshipTarget>::on_replace)
+        });
+        if *linked_spawn {
+            on_despawn = Some(quote! {
+                Some(<Self as bevy_mod_ffi::relationship::RelationshipTarget>::on_despawn)
+            });
+        }
+        relationship_impl = Some(quote! {
+            impl bevy_mod_ffi::relationship::RelationshipTarget for #name {
+                const LINKED_SPAWN: bool = #linked_spawn;
+
+                type Relationship = #source;
+
+                fn collection(&self) -> &Vec<bevy_mod_ffi::prelude::Entity> {
+                    &self.#field
+                }
+
+                fn collection_mut_risky(&mut self) -> &mut Vec<bevy_mod_ffi::prelude::Entity> {
+                    &mut self.#field
+                }
+
+                fn from_collection_risky(collection: Vec<bevy_mod_ffi::prelude::Entity>) -> Self {
+                    Self {
+                        #field: collection,
+                        #(#others: Default::default(),)*
+                    }
+                }
+            }
+        });
+    }
+
+    let [on_add, on_insert, on_replace, on_remove, on_despawn] =
+        [on_add, on_insert, on_replace, on_remove, on_despawn]
+            .map(|hook| hook.unwrap_or_else(|| quote! { None }));
+
+    let register_required = (!required.is_empty()).then(|| {
+        let registrations = required.iter().map(|RequiredComponent { path, constructor }| {
+            let constructor = match constructor {
+                Some(expr) => quote! { || #expr },
+                None => quote! { <#path as Default>::default },
+            };
+            quote! {
+                required_components.register_required::<#path>(#constructor);
+            }
+        });
+
+        quote! {
+            fn register_required_components(
+                _component_id: bevy_mod_ffi::world::ComponentId,
+                required_components: &mut bevy_mod_ffi::component::RequiredComponentsRegistrator<'_>,
+            ) {
+                #(#registrations)*
+            }
+        }
+    });
+
+    let entity_fields: Vec<Member> = match &input.data {
+        Data::Struct(data) => data
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| field.attrs.iter().any(|a| a.path().is_ident("entities")))
+            .map(|(index, field)| match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(index.into()),
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    let map_entities = (!entity_fields.is_empty()).then(|| {
+        quote! {
+            fn map_entities<E>(this: &mut Self, mapper: &mut E)
+            where
+                E: bevy_mod_ffi::component::EntityMapper,
+            {
+                #(bevy_mod_ffi::component::MapEntities::map_entities(&mut this.#entity_fields, mapper);)*
+            }
+        }
+    });
+
     let expanded = quote! {
         impl bevy_mod_ffi::component::SharedComponent for #name {
             type Mutability = #mutability;
@@ -103,6 +323,425 @@ pub fn derive_shared_component(input: TokenStream) -> TokenStream {
             fn on_despawn() -> Option<for<'w> fn(bevy_mod_ffi::world::DeferredWorld<'w>, bevy_mod_ffi::component::HookContext)> {
                 #on_despawn
             }
+
+            #register_required
+
+            #clone_behavior
+
+            #map_entities
+        }
+
+        #relationship_impl
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Returns the field holding the entities of a relationship, which is either the only field or
+/// the one marked `#[relationship]`, along with the remaining fields.
+fn relationship_field(input: &DeriveInput) -> syn::Result<(Member, Vec<Member>)> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "relationships can only be derived for structs",
+        ));
+    };
+
+    let members: Vec<_> = data
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let member = match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(index.into()),
+            };
+            let marked = field
+                .attrs
+                .iter()
+                .any(|a| a.path().is_ident("relationship"));
+            (member, marked)
+        })
+        .collect();
+
+    let index = if members.len() == 1 {
+        0
+    } else {
+        members
+            .iter()
+            .position(|(_, marked)| *marked)
+            .ok_or_else(|| {
+                syn::Error::new_spanned(
+                    input,
+                    "relationships with more than one field must mark one with `#[relationship]`",
+                )
+            })?
+    };
+
+    let mut members: Vec<_> = members.into_iter().map(|(member, _)| member).collect();
+    let field = members.remove(index);
+    Ok((field, members))
+}
+
+/// A component listed in `#[require(...)]`, with an optional `= expr` constructor.
+struct RequiredComponent {
+    path: Path,
+    constructor: Option<Expr>,
+}
+
+impl syn::parse::Parse for RequiredComponent {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        let constructor = if input.parse::<Option<Token![=]>>()?.is_some() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self { path, constructor })
+    }
+}
+
+/// Derives `SystemParam` for a struct whose fields are all system params.
+///
+/// The struct may only have the lifetimes `'w` and `'s`.
+#[proc_macro_derive(SystemParam)]
+pub fn derive_system_param(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return syn::Error::new_spanned(&input, "SystemParam can only be derived for structs")
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    let mut world_lifetime = Lifetime::new("'__w", Span::call_site());
+    let mut state_lifetime = Lifetime::new("'__s", Span::call_site());
+    let mut lifetimes = Vec::new();
+    for lifetime in input.generics.lifetimes() {
+        let lifetime = &lifetime.lifetime;
+        match lifetime.ident.to_string().as_str() {
+            "w" => world_lifetime = lifetime.clone(),
+            "s" => state_lifetime = lifetime.clone(),
+            _ => {
+                return syn::Error::new_spanned(
+                    lifetime,
+                    "SystemParam structs may only have the lifetimes 'w and 's",
+                )
+                .to_compile_error()
+                .into();
+            }
+        }
+        lifetimes.push(lifetime.clone());
+    }
+
+    // Params are named with `'static` lifetimes, as `Query<'static, 'static, D>` yields a
+    // `Query<'w, 's, D>` item with the lifetimes of `get_param`.
+    let param_types: Vec<Type> = fields
+        .iter()
+        .map(|field| {
+            let mut ty = field.ty.clone();
+            StaticLifetimes(&lifetimes).visit_type_mut(&mut ty);
+            ty
+        })
+        .collect();
+    let field_vars: Vec<Ident> = (0..param_types.len())
+        .map(|index| format_ident!("__param_{}", index))
+        .collect();
+
+    let constructor = match fields {
+        Fields::Named(named) => {
+            let idents = named.named.iter().map(|field| &field.ident);
+            quote! {
+                #name {
+                    #(#idents: unsafe { <#param_types as bevy_mod_ffi::system::SystemParam>::get_param(#field_vars, cursor) },)*
+                }
+            }
+        }
+        Fields::Unnamed(_) => quote! {
+            #name(
+                #(unsafe { <#param_types as bevy_mod_ffi::system::SystemParam>::get_param(#field_vars, cursor) },)*
+            )
+        },
+        Fields::Unit => quote! { #name },
+    };
+
+    let type_params: Vec<_> = input.generics.type_params().collect();
+    let type_param_idents: Vec<_> = type_params.iter().map(|param| &param.ident).collect();
+    let anonymous_lifetimes = lifetimes.iter().map(|_| quote! { '_ });
+    let item_lifetimes = lifetimes.iter().map(|lifetime| {
+        if lifetime.ident == "w" {
+            &world_lifetime
+        } else {
+            &state_lifetime
+        }
+    });
+    let where_clause = &input.generics.where_clause;
+
+    let expanded = quote! {
+        unsafe impl<#(#type_params,)*> bevy_mod_ffi::system::SystemParam
+            for #name<#(#anonymous_lifetimes,)* #(#type_param_idents,)*>
+        #where_clause
+        {
+            type State = (#(<#param_types as bevy_mod_ffi::system::SystemParam>::State,)*);
+
+            type Item<#world_lifetime, #state_lifetime> = #name<#(#item_lifetimes,)* #(#type_param_idents,)*>;
+
+            fn build(
+                world: &mut bevy_mod_ffi::world::World,
+                builder: &mut bevy_mod_ffi::system::ParamBuilder,
+            ) -> Self::State {
+                (#(<#param_types as bevy_mod_ffi::system::SystemParam>::build(world, builder),)*)
+            }
+
+            #[allow(unused_variables)]
+            unsafe fn get_param<#world_lifetime, #state_lifetime>(
+                state: &#state_lifetime mut Self::State,
+                cursor: &mut bevy_mod_ffi::system::ParamCursor<'_>,
+            ) -> Self::Item<#world_lifetime, #state_lifetime> {
+                let (#(#field_vars,)*) = state;
+                #constructor
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Replaces the given lifetimes with `'static`.
+struct StaticLifetimes<'a>(&'a [Lifetime]);
+
+impl VisitMut for StaticLifetimes<'_> {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if self.0.contains(lifetime) {
+            *lifetime = Lifetime::new("'static", lifetime.span());
+        }
+    }
+}
+
+/// Derives `QueryData` for a struct with named fields, generating a `{Name}Item` struct
+/// with the item of each field.
+///
+/// Structs are read-only unless marked with `#[query_data(mutable)]`.
+#[proc_macro_derive(QueryData, attributes(query_data))]
+pub fn derive_query_data(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let vis = &input.vis;
+    let item_name = format_ident!("{}Item", name);
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(named) => &named.named,
+            _ => {
+                return syn::Error::new_spanned(
+                    &input,
+                    "QueryData can only be derived for structs with named fields",
+                )
+                .to_compile_error()
+                .into();
+            }
+        },
+        _ => {
+            return syn::Error::new_spanned(&input, "QueryData can only be derived for structs")
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    if !input.generics.params.is_empty() {
+        return syn::Error::new_spanned(&input.generics, "QueryData structs can't be generic")
+            .to_compile_error()
+            .into();
+    }
+
+    let mut is_mutable = false;
+    for attr in &input.attrs {
+        if !attr.path().is_ident("query_data") {
+            continue;
+        }
+
+        if let Err(error) = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("mutable") {
+                is_mutable = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported query_data attribute"))
+            }
+        }) {
+            return error.to_compile_error().into();
+        }
+    }
+
+    let field_idents: Vec<_> = fields.iter().map(|field| &field.ident).collect();
+    let field_vis: Vec<_> = fields.iter().map(|field| &field.vis).collect();
+    let field_types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+    let field_vars: Vec<Ident> = (0..fields.len())
+        .map(|index| format_ident!("__state_{}", index))
+        .collect();
+
+    let query_data = quote! { bevy_mod_ffi::query::QueryData };
+
+    // Read-only structs assert that each field is read-only too.
+    let read_only = if is_mutable {
+        quote! {}
+    } else {
+        quote! {
+            unsafe impl bevy_mod_ffi::query::ReadOnlyQueryData for #name
+            where
+                #(#field_types: bevy_mod_ffi::query::ReadOnlyQueryData,)*
+            {
+            }
+        }
+    };
+
+    let expanded = quote! {
+        #vis struct #item_name<'w, 's> {
+            #(#field_vis #field_idents: <#field_types as #query_data>::Item<'w, 's>,)*
+            #[doc(hidden)]
+            #vis _marker: ::core::marker::PhantomData<(&'w (), &'s ())>,
+        }
+
+        impl #query_data for #name {
+            type Item<'w, 's> = #item_name<'w, 's>;
+            type State = (#(<#field_types as #query_data>::State,)*);
+
+            fn build_query(builder: &mut bevy_mod_ffi::query::QueryBuilder) {
+                #(<#field_types as #query_data>::build_query(builder);)*
+            }
+
+            fn build_state(world: &mut bevy_mod_ffi::world::World) -> Self::State {
+                (#(<#field_types as #query_data>::build_state(world),)*)
+            }
+
+            fn from_entity<'w, 's>(
+                entity: &mut bevy_mod_ffi::world::FilteredEntityMut<'w>,
+                state: &'s mut Self::State,
+            ) -> Self::Item<'w, 's> {
+                let (#(#field_vars,)*) = state;
+                #item_name {
+                    #(#field_idents: <#field_types as #query_data>::from_entity(entity, #field_vars),)*
+                    _marker: ::core::marker::PhantomData,
+                }
+            }
+
+            fn component_id<C: 'static>(
+                state: &Self::State,
+            ) -> Option<bevy_mod_ffi::prelude::ComponentId> {
+                let (#(#field_vars,)*) = state;
+                None
+                    #(.or_else(|| <#field_types as #query_data>::component_id::<C>(#field_vars)))*
+            }
+
+            fn transmute_state<Source: #query_data>(
+                source: &Source::State,
+            ) -> Option<Self::State> {
+                Some((#(<#field_types as #query_data>::transmute_state::<Source>(source)?,)*))
+            }
+
+            fn fetches(
+                state: &Self::State,
+                fetches: &mut Vec<bevy_mod_ffi::bevy_mod_ffi_core::FetchComponent>,
+            ) {
+                let (#(#field_vars,)*) = state;
+                #(<#field_types as #query_data>::fetches(#field_vars, fetches);)*
+            }
+
+            unsafe fn from_fetch<'w, 's>(
+                entity: bevy_mod_ffi::prelude::Entity,
+                components: &mut ::core::slice::Iter<'_, *mut u8>,
+                state: &'s mut Self::State,
+            ) -> Self::Item<'w, 's> {
+                let (#(#field_vars,)*) = state;
+                #item_name {
+                    #(#field_idents: unsafe {
+                        <#field_types as #query_data>::from_fetch(entity, components, #field_vars)
+                    },)*
+                    _marker: ::core::marker::PhantomData,
+                }
+            }
+        }
+
+        #read_only
+
+        // Reads each field so the struct, only used as a type, doesn't warn as dead code.
+        const _: () = {
+            #[allow(dead_code)]
+            fn dead_code_workaround(query: #name) {
+                #(let _ = query.#field_idents;)*
+            }
+        };
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Derives `Bundle` for a struct whose fields are all components or bundles.
+///
+/// Fields marked with `#[bundle(ignore)]` aren't inserted.
+#[proc_macro_derive(Bundle, attributes(bundle))]
+pub fn derive_bundle(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return syn::Error::new_spanned(&input, "Bundle can only be derived for structs")
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    let mut members = Vec::new();
+    let mut types = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let mut is_ignored = false;
+        for attr in &field.attrs {
+            if !attr.path().is_ident("bundle") {
+                continue;
+            }
+
+            if let Err(error) = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("ignore") {
+                    is_ignored = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported bundle attribute"))
+                }
+            }) {
+                return error.to_compile_error().into();
+            }
+        }
+
+        if !is_ignored {
+            members.push(match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(index.into()),
+            });
+            types.push(&field.ty);
+        }
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let expanded = quote! {
+        impl #impl_generics bevy_mod_ffi::world::Bundle for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn component_ids(
+                world: &bevy_mod_ffi::world::World,
+                ids: &mut Vec<bevy_mod_ffi::world::ComponentId>,
+            ) {
+                #(<#types as bevy_mod_ffi::world::Bundle>::component_ids(world, ids);)*
+            }
+
+            #[allow(unused_variables)]
+            fn write_components(self, writer: &mut bevy_mod_ffi::world::BundleWriter) {
+                #(bevy_mod_ffi::world::Bundle::write_components(self.#members, writer);)*
+            }
         }
     };
 
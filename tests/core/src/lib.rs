@@ -22,3 +22,153 @@ impl SharedComponent for TestMarker {
     type Mutability = Mutable;
     const STORAGE_TYPE: StorageType = StorageType::Table;
 }
+
+#[derive(Component, Clone, Copy, Debug, Pod, Zeroable, Reflect)]
+#[repr(C)]
+pub struct Score {
+    pub value: i32,
+}
+
+impl SharedComponent for Score {
+    type Mutability = Mutable;
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+}
+
+#[derive(Component, Clone, Copy, Debug, Pod, Zeroable, Reflect)]
+#[repr(C)]
+pub struct Position {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl SharedComponent for Position {
+    type Mutability = Mutable;
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+}
+
+#[derive(Component, Clone, Copy, Debug, Pod, Zeroable, Reflect)]
+#[repr(C)]
+pub struct Velocity {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl SharedComponent for Velocity {
+    type Mutability = Mutable;
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+}
+
+#[derive(Component, Clone, Copy, Debug, Pod, Zeroable, Reflect)]
+#[repr(C)]
+pub struct Collider {
+    pub hits: i32,
+}
+
+impl SharedComponent for Collider {
+    type Mutability = Mutable;
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+}
+
+#[derive(Component, Clone, Copy, Debug, Pod, Zeroable, Reflect)]
+#[repr(C)]
+pub struct Priority {
+    pub value: i32,
+    pub rank: i32,
+}
+
+impl SharedComponent for Priority {
+    type Mutability = Mutable;
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+}
+
+#[derive(Component, Clone, Copy, Debug, Pod, Zeroable, Reflect)]
+#[repr(C)]
+pub struct Health {
+    pub value: i32,
+}
+
+impl SharedComponent for Health {
+    type Mutability = Mutable;
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+}
+
+#[derive(Component, Clone, Copy, Debug, Pod, Zeroable, Reflect)]
+#[repr(C)]
+pub struct Armor {
+    pub value: i32,
+}
+
+impl SharedComponent for Armor {
+    type Mutability = Mutable;
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+}
+
+#[derive(Component, Clone, Copy, Debug, Pod, Zeroable, Reflect)]
+#[repr(C)]
+pub struct Stamina {
+    pub current: i32,
+    pub max: i32,
+}
+
+impl SharedComponent for Stamina {
+    type Mutability = Mutable;
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+}
+
+#[derive(Component, Clone, Copy, Debug, Pod, Zeroable, Reflect)]
+#[repr(C)]
+pub struct Level {
+    pub value: u32,
+}
+
+impl SharedComponent for Level {
+    type Mutability = Mutable;
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+}
+
+#[derive(Component, Clone, Copy, Debug, Pod, Zeroable, Reflect)]
+#[repr(C)]
+pub struct Experience {
+    pub points: u32,
+}
+
+impl SharedComponent for Experience {
+    type Mutability = Mutable;
+    const STORAGE_TYPE: StorageType = StorageType::SparseSet;
+}
+
+#[derive(Component, Clone, Copy, Debug, Default, Pod, Zeroable, Reflect)]
+#[repr(C)]
+pub struct Hunger {
+    pub value: i32,
+}
+
+impl SharedComponent for Hunger {
+    type Mutability = Mutable;
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+}
+
+#[derive(Component, Clone, Copy, Debug, Default, Pod, Zeroable, Reflect)]
+#[repr(C)]
+pub struct Decay {
+    pub rate: f32,
+}
+
+impl SharedComponent for Decay {
+    type Mutability = Mutable;
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+}
+
+#[derive(Resource, Clone, Copy, Debug, Default, Pod, Zeroable, Reflect)]
+#[repr(C)]
+pub struct Tally {
+    pub wounds: u32,
+    pub alarms: u32,
+    pub scopes: u32,
+}
+
+#[derive(Event, Clone, Copy, Debug, Pod, Zeroable, Reflect)]
+#[repr(C)]
+pub struct Alarm {
+    pub level: u32,
+}
@@ -1,6 +1,19 @@
-use bevy_mod_ffi::prelude::*;
-use bevy_mod_ffi_test_core::{Counter, TestMarker};
+use bevy_mod_ffi::{
+    component::{ComponentCloneCtx, MapEntities, RelationshipHookMode, SourceComponent},
+    prelude::*,
+};
+use bevy_mod_ffi_test_core::{
+    Alarm, Armor, Collider, Counter, Decay, Experience, Health, Hunger, Level, Position, Priority,
+    Score, Stamina, Tally, TestMarker, Velocity,
+};
 use bevy_reflect::TypePath;
+use std::{
+    cell::Cell,
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Zeroable, Pod, TypePath)]
@@ -11,9 +24,237 @@ impl SharedComponent for GuestMarker {
     const STORAGE_TYPE: StorageType = StorageType::Table;
 }
 
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, TypePath, SharedComponent)]
+#[require(Hunger = Hunger { value: 50 }, Decay)]
+struct Zombie;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Zeroable, Pod, TypePath, SharedComponent)]
+#[require(Zombie)]
+struct Ghoul;
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Zeroable, Pod)]
+struct EntityBits(u64);
+
+impl MapEntities for EntityBits {
+    fn map_entities<E: EntityMapper>(&mut self, mapper: &mut E) {
+        self.0 = mapper.get_mapped(Entity::from_bits(self.0)).to_bits();
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Zeroable, Pod, TypePath, SharedComponent)]
+struct Leash {
+    #[entities]
+    owner: EntityBits,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Zeroable, Pod, TypePath, SharedComponent)]
+#[component(clone_behavior = Custom(split_charge))]
+struct Charge {
+    value: i32,
+}
+
+fn split_charge(source: &SourceComponent, ctx: &mut ComponentCloneCtx) {
+    let charge = source.read::<Charge>().unwrap();
+    ctx.write_target_component(Charge {
+        value: charge.value / 2,
+    });
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Zeroable, Pod, TypePath, SharedComponent)]
+#[component(clone_behavior = Ignore)]
+struct Soul;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Zeroable, Pod, TypePath, SharedComponent)]
+#[component(immutable)]
+struct Badge {
+    rank: u32,
+}
+
+static DROPPED_WAYPOINTS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone, Debug)]
+struct Waypoint(u32);
+
+impl Drop for Waypoint {
+    fn drop(&mut self) {
+        DROPPED_WAYPOINTS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[derive(Clone, Debug, TypePath, SharedComponent)]
+#[component(clone_behavior = clone::<Self>())]
+struct Route {
+    waypoints: Vec<Waypoint>,
+}
+
+/// Spawns a non-`Pod` component outside of `main`, so only the test that calls this owns one.
+#[unsafe(no_mangle)]
+extern "C" fn spawn_route(world_ptr: *mut bevy_mod_ffi::bevy_mod_ffi_core::world) {
+    let mut world = unsafe { World::from_ptr(world_ptr) };
+    world.spawn(Route {
+        waypoints: (0..3).map(Waypoint).collect(),
+    });
+    world.run_system((), |mut query: Query<&mut Route>| {
+        for route in query.iter_mut() {
+            route.waypoints.push(Waypoint(3));
+        }
+    });
+
+    let total: u32 = world
+        .run_system((), |mut query: Query<&Route>| {
+            query
+                .iter_mut()
+                .flat_map(|route| &route.waypoints)
+                .map(|waypoint| waypoint.0)
+                .sum()
+        })
+        .unwrap();
+    assert_eq!(total, 6, "Expected the route to own its waypoints");
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn dropped_waypoints() -> usize {
+    DROPPED_WAYPOINTS.load(Ordering::Relaxed)
+}
+
+static TRACKER_ADDED: Mutex<Option<HookContext>> = Mutex::new(None);
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Zeroable, Pod, TypePath, SharedComponent)]
+#[component(on_add = on_tracker_added)]
+struct Tracker;
+
+fn on_tracker_added(_world: DeferredWorld, context: HookContext) {
+    *TRACKER_ADDED.lock().unwrap() = Some(context);
+}
+
+thread_local! {
+    // Tests run in parallel, so hooks on host components are counted per thread
+    static DECAY_ADDED: Cell<usize> = const { Cell::new(0) };
+}
+
+fn on_decay_added(_world: DeferredWorld, _context: HookContext) {
+    DECAY_ADDED.set(DECAY_ADDED.get() + 1);
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn decay_added() -> usize {
+    DECAY_ADDED.get()
+}
+
+thread_local! {
+    static DROPPED_GUARDS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Counts the drops of the closures that capture it.
+struct DropGuard;
+
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        DROPPED_GUARDS.set(DROPPED_GUARDS.get() + 1);
+    }
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn dropped_guards() -> usize {
+    DROPPED_GUARDS.get()
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Zeroable, Pod, TypePath, SharedComponent)]
+#[component(on_add = on_wound_added)]
+struct Wound {
+    damage: u32,
+}
+
+fn on_wound_added(mut world: DeferredWorld, context: HookContext) {
+    let damage = world.get::<Wound>(context.entity).unwrap().damage;
+    assert!(world.entity(context.entity).contains::<Experience>());
+
+    let mut entity = world.entity_mut(context.entity);
+    entity.get_mut::<Experience>().unwrap().points -= damage;
+    drop(entity);
+
+    world.resource_mut::<Tally>().wounds += 1;
+    world.trigger(Alarm { level: damage });
+}
+
+#[derive(Clone, Copy, Debug, TypePath, SharedComponent)]
+#[relationship(relationship_target = TargetedBy)]
+struct Targeting(Entity);
+
+#[derive(Debug, TypePath, SharedComponent)]
+#[relationship_target(relationship = Targeting, linked_spawn)]
+struct TargetedBy(Vec<Entity>);
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, TypePath, SharedComponent)]
+struct Rally;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Zeroable, Pod, TypePath, SharedComponent)]
+#[require(Rally)]
+struct Unit {
+    wave: u32,
+}
+
+fn total_velocity(mut query: Query<&Velocity>) -> f32 {
+    query.iter_mut().map(|velocity| velocity.x).sum()
+}
+
+#[allow(clippy::type_complexity)]
+fn fortify_armor(mut set: ParamSet<(Query<&mut Armor>, Query<&mut Armor, With<Health>>)>) {
+    for armor in set.p0().iter_mut() {
+        armor.value += 1;
+    }
+    for armor in set.p1().iter_mut() {
+        armor.value *= 10;
+    }
+}
+
+#[derive(SystemParam)]
+struct Census<'w, 's> {
+    health: Query<'w, 's, &'static Health>,
+    armor: Query<'w, 's, &'static Armor>,
+    visits: Local<'s, u32>,
+}
+
+#[derive(QueryData)]
+#[query_data(mutable)]
+struct Runner {
+    entity: Entity,
+    stamina: &'static mut Stamina,
+}
+
+#[derive(QueryData)]
+struct RunnerView {
+    stamina: &'static Stamina,
+}
+
+#[derive(Bundle)]
+struct ProgressBundle {
+    experience: Experience,
+}
+
+#[derive(Bundle)]
+struct HeroBundle {
+    level: Level,
+    progress: ProgressBundle,
+    #[bundle(ignore)]
+    _title: &'static str,
+}
+
 #[bevy_mod_ffi::main]
 fn main(world: &mut World) {
     world.register_component::<GuestMarker>();
+    world.register_component::<Route>();
 
     world.spawn((GuestMarker, Counter { value: 42 }, TestMarker));
 
@@ -35,4 +276,455 @@ fn main(world: &mut World) {
     });
 
     world.spawn((GuestMarker, Counter { value: 0 }));
+
+    for value in 0..300 {
+        world.spawn(Score { value });
+    }
+
+    world.run_system((), |mut query: Query<(Entity, &mut Score)>| {
+        for (_entity, score) in query.iter_mut() {
+            score.value += 1;
+        }
+    });
+
+    for i in 0..100 {
+        world.spawn(Position { x: i as f32, y: 1. });
+    }
+
+    world.run_system((), |mut query: Query<&mut Position>| {
+        let mut rows = 0;
+        let mut chunks = 0;
+        query.for_each_chunk(|entities: &[Entity], positions: &mut [Position]| {
+            assert_eq!(entities.len(), positions.len());
+            rows += positions.len();
+            chunks += 1;
+
+            for position in positions {
+                position.y += position.x;
+            }
+        });
+        assert_eq!(rows, 100, "Expected 100 chunked rows, found {}", rows);
+        assert_eq!(chunks, 1, "Expected one chunk per table, found {}", chunks);
+    });
+
+    for i in 0..500 {
+        world.spawn(Velocity { x: i as f32, y: 0. });
+    }
+
+    world.run_system((), |mut query: Query<&mut Velocity>| {
+        query
+            .par_iter_mut()
+            .batch_size(64)
+            .for_each(|velocity: &mut Velocity| {
+                velocity.y = velocity.x * 2.;
+            });
+    });
+
+    world.run_system((), |mut query: Query<(Entity, &mut Velocity)>| {
+        let total = total_velocity(query.transmute_lens::<&Velocity>().query());
+        let expected = (0..500).sum::<i32>() as f32;
+        assert_eq!(
+            total, expected,
+            "Expected total velocity {}, found {}",
+            expected, total
+        );
+    });
+
+    world.run_system((), |mut query: Query<(Entity, &Velocity)>| {
+        let mut lens = query.transmute_lens_filtered::<&Velocity, With<Velocity>>();
+        let total: f32 = lens.query().iter_mut().map(|velocity| velocity.x).sum();
+        let expected = (0..500).sum::<i32>() as f32;
+        assert_eq!(
+            total, expected,
+            "Expected filtered total velocity {}, found {}",
+            expected, total
+        );
+    });
+
+    world.run_system((), |mut query: Query<(Entity, &Position)>| {
+        let pairs = query.iter_combinations_mut::<2>().count();
+        assert_eq!(pairs, 4950, "Expected 4950 pairs, found {}", pairs);
+    });
+
+    for _ in 0..4 {
+        world.spawn(Collider { hits: 0 });
+    }
+
+    world.run_system((), |mut query: Query<&mut Collider>| {
+        let mut combinations = query.iter_combinations_mut();
+        while let Some([a, b]) = combinations.fetch_next() {
+            a.hits += 1;
+            b.hits += 1;
+        }
+    });
+
+    let mut priorities = world.query::<&Priority>();
+    assert_eq!(priorities.iter_mut(world).count(), 0);
+
+    for value in [3, 1, 4, 1, 5] {
+        world.spawn(Priority { value, rank: -1 });
+    }
+
+    priorities.update_archetypes(world);
+    let count = priorities.iter_mut(world).count();
+    assert_eq!(
+        count, 5,
+        "Expected cached query to match 5 entities, found {}",
+        count
+    );
+
+    // The local state is kept between runs, so it has to pick up archetypes created after it
+    let mut count_priorities =
+        SystemState::<(Local<QueryState<&Priority>>, DeferredWorld)>::new(world).build(
+            |mut state: Local<QueryState<&Priority>>, mut world: DeferredWorld| {
+                world.query(&mut state).iter_mut().count() as u32
+            },
+        );
+    let count = world.run_system_ref((), &mut count_priorities);
+    assert_eq!(count, Some(5), "Expected local query to match 5 entities");
+
+    let moving: Vec<Entity> = (0..2)
+        .map(|value| {
+            world
+                .spawn((Priority { value, rank: -1 }, Velocity { x: 0., y: 0. }))
+                .id()
+        })
+        .collect();
+    let count = world.run_system_ref((), &mut count_priorities);
+    assert_eq!(
+        count,
+        Some(7),
+        "Expected local query to match entities in a new archetype"
+    );
+    for entity in moving {
+        world.despawn(entity);
+    }
+
+    world.run_system((), |mut query: Query<&mut Priority>| {
+        for (rank, priority) in query
+            .iter_sort_by_key::<Priority, _>(|priority| priority.value)
+            .enumerate()
+        {
+            priority.rank = rank as i32;
+        }
+    });
+
+    world.run_system((), |mut query: Query<(Entity, &Priority)>| {
+        let entities: Vec<Entity> = query
+            .iter_sort_by_entity()
+            .map(|(entity, _)| entity)
+            .collect();
+        assert!(
+            entities.is_sorted(),
+            "Expected entities in ascending order, found {:?}",
+            entities
+        );
+    });
+
+    for value in 1..=3 {
+        world.spawn((Health { value: 10 }, Armor { value }));
+    }
+    world.spawn(Health { value: 10 });
+    world.spawn(Armor { value: 100 });
+
+    world.run_system(
+        (),
+        |mut health: Query<&mut Health>, mut armor: Query<&Armor>| {
+            let mut joined = health.join::<&Armor, (&mut Health, &Armor)>(&mut armor);
+            for (health, armor) in joined.query().iter_mut() {
+                health.value += armor.value;
+            }
+        },
+    );
+
+    world.run_system(
+        (),
+        |mut health: Query<&Health>, mut armor: Query<&Armor>| {
+            let mut joined = health.join_filtered::<&Armor, (), &Armor, With<Health>>(&mut armor);
+            let count = joined.query().iter_mut().count();
+            assert_eq!(
+                count, 3,
+                "Expected 3 filtered joined entities, found {}",
+                count
+            );
+        },
+    );
+
+    world.run_system((), fortify_armor);
+
+    let value = world.run_system((), |marked: Single<&Counter, With<TestMarker>>| {
+        marked.value
+    });
+    assert_eq!(
+        value,
+        Some(84),
+        "Expected system with a single match to run"
+    );
+
+    let ran = world.run_system((), |_scores: Single<&Score>| {});
+    assert!(
+        ran.is_none(),
+        "Expected system with many matches to be skipped"
+    );
+
+    let matched = world.run_system((), |mut armor: Populated<&mut Armor>| {
+        armor.iter_mut().count() as u32
+    });
+    assert_eq!(
+        matched,
+        Some(4),
+        "Expected populated query to match 4 entities"
+    );
+
+    let ran = world.run_system((), |_armor: Populated<&Armor, With<Counter>>| {});
+    assert!(
+        ran.is_none(),
+        "Expected system with an empty query to be skipped"
+    );
+
+    let census: u32 = world
+        .run_system((), |mut census: Census| {
+            *census.visits += 1;
+            census.health.iter_mut().count() as u32 * 100
+                + census.armor.iter_mut().count() as u32 * 10
+                + *census.visits
+        })
+        .unwrap();
+    assert_eq!(census, 441, "Expected derived param to combine its fields");
+
+    for max in [10, 20, 30] {
+        world.spawn(Stamina { current: 0, max });
+    }
+
+    world.run_system((), |mut query: Query<Runner>| {
+        for RunnerItem {
+            entity, stamina, ..
+        } in query.iter_mut()
+        {
+            assert_ne!(entity, Entity::PLACEHOLDER);
+            stamina.current = stamina.max;
+        }
+    });
+
+    let total: i32 = world
+        .run_system((), |mut query: Query<RunnerView>| {
+            query.iter_mut().map(|view| view.stamina.current).sum()
+        })
+        .unwrap();
+    assert_eq!(
+        total, 60,
+        "Expected derived query data to read restored stamina"
+    );
+
+    world.spawn(HeroBundle {
+        level: Level { value: 7 },
+        progress: ProgressBundle {
+            experience: Experience { points: 1200 },
+        },
+        _title: "Hero",
+    });
+
+    world.register_component::<Zombie>();
+    world.register_component::<Ghoul>();
+
+    world.spawn(Zombie);
+    world.spawn((Zombie, Hunger { value: 5 }));
+    world.spawn(Ghoul);
+
+    let zombies: u32 = world
+        .run_system((), |mut query: Query<(&Hunger, &Decay), With<Zombie>>| {
+            query.iter_mut().count() as u32
+        })
+        .unwrap();
+    assert_eq!(zombies, 3, "Expected required components on every zombie");
+
+    world.register_component::<Leash>();
+    world.register_component::<Charge>();
+    world.register_component::<Soul>();
+
+    world.spawn((
+        Leash {
+            owner: EntityBits(0),
+        },
+        Charge { value: 80 },
+        Soul,
+    ));
+    world.run_system((), |mut query: Query<(Entity, &mut Leash)>| {
+        for (entity, leash) in query.iter_mut() {
+            leash.owner = EntityBits(entity.to_bits());
+        }
+    });
+
+    world.register_component::<Badge>();
+    world.spawn(Badge { rank: 3 });
+
+    let rank: u32 = world
+        .run_system((), |mut query: Query<&Badge>| {
+            query.iter_mut().map(|badge| badge.rank).sum()
+        })
+        .unwrap();
+    assert_eq!(rank, 3, "Expected immutable components to be readable");
+
+    world.register_component::<Tracker>();
+    let tracked = world.spawn(Tracker).id();
+
+    let context = TRACKER_ADDED.lock().unwrap().take().unwrap();
+    assert_eq!(context.entity, tracked);
+    assert!(matches!(
+        context.relationship_hook_mode,
+        RelationshipHookMode::Run
+    ));
+    // The test host enables `track_location`, so the caller is where the host spawn inserts the bundle
+    let caller = context
+        .caller
+        .expect("Expected the caller of the hook to be tracked");
+    assert!(
+        caller.file.ends_with("host_sys/src/world/mod.rs") && caller.line > 0,
+        "Expected the host spawn to be the caller, found {caller}"
+    );
+
+    world.add_observer(|alarm: On<Alarm>, mut world: DeferredWorld| {
+        world.resource_mut::<Tally>().alarms += alarm.level;
+    });
+    world.register_component::<Wound>();
+    world.spawn((Experience { points: 100 }, Wound { damage: 30 }));
+
+    let tally = world.get_resource::<Tally>().unwrap();
+    assert_eq!(tally.wounds, 1);
+    assert_eq!(tally.alarms, 30);
+
+    let leveled = world.spawn(Level { value: 3 }).id();
+    assert_eq!(world.get::<Level>(leveled).unwrap().value, 3);
+    world.get_mut::<Level>(leveled).unwrap().value += 1;
+    assert_eq!(world.get::<Level>(leveled).unwrap().value, 4);
+    assert!(world.get::<Wound>(leveled).is_none());
+
+    assert!(world.contains_resource::<Tally>());
+    world.resource_mut::<Tally>().scopes += 1;
+    let level = world.resource_scope(|world, tally: &mut Tally| {
+        assert!(!world.contains_resource::<Tally>());
+        tally.scopes += 1;
+        world.get::<Level>(leveled).unwrap().value
+    });
+    assert_eq!(level, 4);
+    assert_eq!(world.resource::<Tally>().scopes, 2);
+
+    let entity_count = world.entities().len();
+    assert_eq!(world.iter_entities().count(), entity_count as usize);
+    assert!(world.iter_entities().any(|entity| entity == leveled));
+
+    assert!(world.despawn(leveled));
+    assert!(!world.despawn(leveled));
+    assert_eq!(world.entities().len(), entity_count - 1);
+    assert!(world.get::<Level>(leveled).is_none());
+
+    world.component_hooks::<Decay>().on_add(on_decay_added);
+    let added = DECAY_ADDED.get();
+    world.spawn(Decay { rate: 0.5 });
+    assert_eq!(
+        DECAY_ADDED.get(),
+        added + 1,
+        "Expected a guest hook on a host component to run"
+    );
+
+    let parent = world
+        .spawn(Level { value: 10 })
+        .with_children(|spawner| {
+            spawner.spawn(Level { value: 11 });
+            spawner.spawn(Level { value: 12 });
+        })
+        .id();
+    let adopted = world.spawn(Level { value: 13 }).id();
+    world.entity_mut(parent).add_child(adopted);
+    let stray = world.spawn(Level { value: 14 }).set_parent(parent).id();
+
+    let children = world.children(parent).unwrap();
+    assert_eq!(children.len(), 4);
+    assert_eq!(children[2..], [adopted, stray]);
+    assert_eq!(world.children(stray), None);
+    assert_eq!(world.get::<ChildOf>(stray).unwrap().parent(), parent);
+
+    let child_levels: u32 = world
+        .run_system((), |mut query: Query<(&Level, &ChildOf)>| {
+            query.iter_mut().map(|(level, _)| level.value).sum()
+        })
+        .unwrap();
+    assert_eq!(child_levels, 11 + 12 + 13 + 14);
+
+    world.register_component::<Targeting>();
+    world.register_component::<TargetedBy>();
+    let target = world.spawn(Level { value: 20 }).id();
+    let hunter = world.spawn(Targeting(target)).id();
+    let stalker = world.spawn(Targeting(target)).id();
+    assert_eq!(
+        world.get::<TargetedBy>(target).unwrap().collection(),
+        &[hunter, stalker]
+    );
+
+    world.entity_mut(stalker).remove::<Targeting>();
+    assert_eq!(
+        world.get::<TargetedBy>(target).unwrap().collection(),
+        &[hunter]
+    );
+
+    let decoy = world.spawn(Level { value: 21 }).id();
+    world.entity_mut(hunter).insert(Targeting(decoy));
+    assert!(
+        world.get::<TargetedBy>(target).is_none(),
+        "Expected an empty relationship target to be removed"
+    );
+    assert_eq!(world.get::<TargetedBy>(decoy).unwrap().len(), 1);
+
+    assert!(world.despawn(decoy));
+    assert!(
+        world.get_entity_mut(hunter).is_none(),
+        "Expected a linked source to be despawned with its target"
+    );
+    assert!(world.get_entity_mut(stalker).is_some());
+
+    world.register_component::<Rally>();
+    world.register_component::<Unit>();
+    let units = world.spawn_batch((0..1000).map(|index| {
+        (
+            Unit { wave: index % 4 },
+            Level {
+                value: 1000 + index,
+            },
+        )
+    }));
+    assert_eq!(units.len(), 1000);
+    assert_eq!(world.get::<Unit>(units[5]).unwrap().wave, 1);
+    assert_eq!(world.get::<Level>(units[999]).unwrap().value, 1999);
+
+    let rallied: usize = world
+        .run_system((), |mut query: Query<&Unit, With<Rally>>| {
+            query.iter_mut().count()
+        })
+        .unwrap();
+    assert_eq!(rallied, 1000, "Expected required components in a batch");
+    assert!(world.spawn_batch(std::iter::empty::<Unit>()).is_empty());
+
+    let dropped = DROPPED_GUARDS.get();
+    let guard = DropGuard;
+    world.run_system((), move |mut query: Query<&Unit>| {
+        let _ = &guard;
+        assert_eq!(query.iter_mut().count(), 1000);
+    });
+    assert_eq!(
+        DROPPED_GUARDS.get(),
+        dropped + 1,
+        "Expected a one-shot system to drop its closure"
+    );
+
+    let guard = DropGuard;
+    world.add_observer(move |_alarm: On<Alarm>| {
+        let _ = &guard;
+    });
+    let guard = DropGuard;
+    world
+        .spawn(Level { value: 30 })
+        .observe(move |_alarm: OnEntity<Alarm>| {
+            let _ = &guard;
+        });
+    assert_eq!(DROPPED_GUARDS.get(), dropped + 1);
 }
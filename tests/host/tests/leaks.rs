@@ -0,0 +1,236 @@
+//! Checks that host exports free what they allocate, using an allocator that counts the live
+//! bytes of each thread.
+
+use bevy::prelude::*;
+use bevy_mod_ffi::{
+    SharedRegistry,
+    bevy_mod_ffi_core::{BundleComponent, dyn_system_param},
+};
+use bevy_mod_ffi_host_sys::{
+    system::{
+        bevy_system_drop, bevy_system_state_build, bevy_system_state_get,
+        bevy_system_state_params_drop,
+        param::{
+            bevy_commands_drop, bevy_dyn_system_param_downcast_commands,
+            bevy_param_builder_add_commands, bevy_param_builder_build, bevy_param_builder_new,
+        },
+    },
+    world::{
+        bevy_world_entity_mut, bevy_world_run_system, bevy_world_spawn,
+        entity::{bevy_entity_world_mut_insert, bevy_world_entity_mut_drop},
+    },
+};
+use bevy_mod_ffi_test_core::{Experience, Level, TestMarker};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    ptr, slice,
+};
+
+struct CountingAllocator;
+
+thread_local! {
+    static LIVE_BYTES: Cell<isize> = const { Cell::new(0) };
+    static DROPPED_CLOSURES: Cell<usize> = const { Cell::new(0) };
+}
+
+fn track(bytes: isize) {
+    LIVE_BYTES.with(|live| live.set(live.get() + bytes));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            track(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        track(-(layout.size() as isize));
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            track(new_size as isize - layout.size() as isize);
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn spawn_and_despawn(world: &mut World, level: Level, experience: Experience) {
+    let level_id = world.component_id::<Level>().unwrap();
+    let experience_id = world.component_id::<Experience>().unwrap();
+    let marker_id = world.component_id::<TestMarker>().unwrap();
+
+    let components = [
+        BundleComponent {
+            component_id: level_id.index(),
+            ptr: &level as *const Level as *const u8,
+        },
+        BundleComponent {
+            component_id: marker_id.index(),
+            ptr: &TestMarker as *const TestMarker as *const u8,
+        },
+    ];
+    let insert = [BundleComponent {
+        component_id: experience_id.index(),
+        ptr: &experience as *const Experience as *const u8,
+    }];
+
+    let world_ptr = world as *mut World as *mut bevy_mod_ffi::bevy_mod_ffi_core::world;
+    let mut entity_bits = 0;
+    let mut entity_ptr = ptr::null_mut();
+    unsafe {
+        assert!(bevy_world_spawn(
+            world_ptr,
+            components.as_ptr(),
+            components.len(),
+            &mut entity_bits,
+            &mut entity_ptr,
+        ));
+        bevy_world_entity_mut_drop(entity_ptr);
+
+        assert!(bevy_world_entity_mut(
+            world_ptr,
+            entity_bits,
+            &mut entity_ptr
+        ));
+        assert!(bevy_entity_world_mut_insert(
+            entity_ptr,
+            insert.as_ptr(),
+            insert.len()
+        ));
+        bevy_world_entity_mut_drop(entity_ptr);
+    }
+
+    let entity = Entity::from_bits(entity_bits);
+    assert_eq!(world.get::<Level>(entity).unwrap().value, level.value);
+    assert_eq!(
+        world.get::<Experience>(entity).unwrap().points,
+        experience.points
+    );
+    assert!(world.entity(entity).contains::<TestMarker>());
+    world.despawn(entity);
+    // Despawning buffers removal events until the trackers are cleared
+    world.clear_trackers();
+}
+
+#[test]
+fn test_spawn_does_not_leak() {
+    let mut world = World::new();
+    world.insert_resource(SharedRegistry::default());
+    world.register_component::<Level>();
+    world.register_component::<Experience>();
+    world.register_component::<TestMarker>();
+
+    // The first spawns allocate archetypes and tables that are reused afterwards
+    for _ in 0..2 {
+        spawn_and_despawn(&mut world, Level { value: 1 }, Experience { points: 1 });
+    }
+
+    let baseline = LIVE_BYTES.get();
+    for index in 0..1000 {
+        spawn_and_despawn(
+            &mut world,
+            Level { value: index },
+            Experience { points: index },
+        );
+    }
+
+    assert_eq!(
+        LIVE_BYTES.get() - baseline,
+        0,
+        "Expected spawning and inserting to free every allocation"
+    );
+}
+
+/// Consumes each `Commands` param, like a guest system would.
+unsafe fn take_commands(params: *const *mut dyn_system_param, params_len: usize) {
+    for param in unsafe { slice::from_raw_parts(params, params_len) } {
+        let mut commands = ptr::null_mut();
+        unsafe {
+            assert!(bevy_dyn_system_param_downcast_commands(
+                *param,
+                &mut commands
+            ));
+            bevy_commands_drop(commands);
+        }
+    }
+}
+
+unsafe extern "C" fn run_closure(
+    f_ptr: *mut (),
+    params: *const *mut dyn_system_param,
+    params_len: usize,
+    _input_ptr: *const u8,
+    _output_ptr: *mut u8,
+) {
+    unsafe {
+        *(f_ptr as *mut usize) += 1;
+        take_commands(params, params_len);
+    }
+}
+
+unsafe extern "C" fn drop_closure(f_ptr: *mut ()) {
+    let runs = unsafe { Box::from_raw(f_ptr as *mut usize) };
+    assert_eq!(*runs, 1);
+    DROPPED_CLOSURES.set(DROPPED_CLOSURES.get() + 1);
+}
+
+fn build_and_run_system(world: &mut World) {
+    let world_ptr = world as *mut World as *mut bevy_mod_ffi::bevy_mod_ffi_core::world;
+    unsafe {
+        let mut builder = ptr::null_mut();
+        assert!(bevy_param_builder_new(&mut builder));
+        assert!(bevy_param_builder_add_commands(builder));
+        let mut state = ptr::null_mut();
+        assert!(bevy_param_builder_build(world_ptr, builder, &mut state));
+
+        let mut params = ptr::null_mut();
+        let mut params_len = 0;
+        bevy_system_state_get(world_ptr, state, &mut params, &mut params_len);
+        take_commands(params, params_len as usize);
+        bevy_system_state_params_drop(params, params_len as usize);
+
+        let mut system = ptr::null_mut();
+        bevy_system_state_build(
+            state,
+            Box::into_raw(Box::new(0usize)) as *mut (),
+            run_closure,
+            drop_closure,
+            &mut system,
+        );
+        bevy_world_run_system(world_ptr, system, ptr::null_mut(), ptr::null_mut());
+        bevy_system_drop(system);
+    }
+}
+
+#[test]
+fn test_systems_do_not_leak() {
+    let mut world = World::new();
+    build_and_run_system(&mut world);
+
+    let baseline = LIVE_BYTES.get();
+    let dropped = DROPPED_CLOSURES.get();
+    for _ in 0..100 {
+        build_and_run_system(&mut world);
+    }
+
+    assert_eq!(
+        DROPPED_CLOSURES.get() - dropped,
+        100,
+        "Expected dropping a system to drop its closure"
+    );
+    assert_eq!(
+        LIVE_BYTES.get() - baseline,
+        0,
+        "Expected building and running systems to free every allocation"
+    );
+}
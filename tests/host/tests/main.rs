@@ -1,6 +1,32 @@
-use bevy::prelude::*;
-use bevy_mod_ffi::SharedRegistry;
-use bevy_mod_ffi_test_core::{Counter, TestMarker};
+use bevy::{ecs::component::ComponentId, prelude::*, ptr::OwningPtr};
+use bevy_mod_ffi::{
+    SharedRegistry,
+    bevy_mod_ffi_core::{self, RegisterComponentResult, dyn_system_param},
+};
+use bevy_mod_ffi_host_sys::{
+    CurrentLibraryHandle, LibraryHandle,
+    query::builder::{bevy_query_builder_new, bevy_query_builder_with},
+    system::{
+        bevy_system_drop, bevy_system_state_build,
+        param::{
+            bevy_dyn_system_params_drop, bevy_param_builder_add_populated,
+            bevy_param_builder_build, bevy_param_builder_new,
+        },
+    },
+    world::{bevy_world_register_component, bevy_world_run_system},
+};
+use bevy_mod_ffi_test_core::{
+    Alarm, Armor, Collider, Counter, Decay, Experience, Health, Hunger, Level, Position, Priority,
+    Score, Stamina, Tally, TestMarker, Velocity,
+};
+use std::{
+    ffi::CString,
+    ptr, slice,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
 
 fn get_guest_library_path() -> String {
     let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_string());
@@ -19,15 +45,29 @@ fn get_guest_library_path() -> String {
 }
 
 fn setup_app() -> App {
-    let registry = SharedRegistry::default();
+    let mut registry = SharedRegistry::default();
+    registry.register_event::<Alarm>();
 
     let mut app = App::new();
     app.add_plugins(MinimalPlugins)
         .init_resource::<AppTypeRegistry>()
-        .insert_resource(registry);
+        .insert_resource(registry)
+        .init_resource::<Tally>();
 
     app.world_mut().register_component::<Counter>();
     app.world_mut().register_component::<TestMarker>();
+    app.world_mut().register_component::<Score>();
+    app.world_mut().register_component::<Position>();
+    app.world_mut().register_component::<Velocity>();
+    app.world_mut().register_component::<Collider>();
+    app.world_mut().register_component::<Priority>();
+    app.world_mut().register_component::<Health>();
+    app.world_mut().register_component::<Armor>();
+    app.world_mut().register_component::<Stamina>();
+    app.world_mut().register_component::<Level>();
+    app.world_mut().register_component::<Experience>();
+    app.world_mut().register_component::<Hunger>();
+    app.world_mut().register_component::<Decay>();
     app.update();
 
     app
@@ -104,3 +144,742 @@ fn test_filter_query_works() {
         count
     );
 }
+
+#[test]
+fn test_batched_query_iteration() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+
+    unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let world = app.world_mut();
+    let mut query = world.query::<&Score>();
+    let values: Vec<i32> = query.iter(world).map(|s| s.value).collect();
+
+    assert_eq!(
+        values.len(),
+        300,
+        "Expected 300 entities with Score, found {}",
+        values.len()
+    );
+    assert_eq!(
+        values.iter().sum::<i32>(),
+        (1..=300).sum::<i32>(),
+        "Expected every Score to be incremented once"
+    );
+}
+
+#[test]
+fn test_chunked_query_iteration() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+
+    unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let world = app.world_mut();
+    let mut query = world.query::<&Position>();
+
+    for position in query.iter(world) {
+        assert_eq!(
+            position.y,
+            position.x + 1.,
+            "Expected y to be offset by x, found {:?}",
+            position
+        );
+    }
+}
+
+#[test]
+fn test_parallel_query_iteration() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+
+    unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let world = app.world_mut();
+    let mut query = world.query::<&Velocity>();
+    let velocities: Vec<Velocity> = query.iter(world).copied().collect();
+
+    assert_eq!(
+        velocities.len(),
+        500,
+        "Expected 500 entities with Velocity, found {}",
+        velocities.len()
+    );
+    for velocity in velocities {
+        assert_eq!(
+            velocity.y,
+            velocity.x * 2.,
+            "Expected every Velocity to be visited once, found {:?}",
+            velocity
+        );
+    }
+}
+
+#[test]
+fn test_query_combinations() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+
+    unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let world = app.world_mut();
+    let mut query = world.query::<&Collider>();
+    let hits: Vec<i32> = query.iter(world).map(|c| c.hits).collect();
+
+    assert_eq!(
+        hits,
+        vec![3; 4],
+        "Expected each Collider to be paired with the other 3, found {:?}",
+        hits
+    );
+}
+
+#[test]
+fn test_sorted_query_iteration() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+
+    unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let world = app.world_mut();
+    let mut query = world.query::<(Entity, &Priority)>();
+    let mut priorities: Vec<(Entity, Priority)> = query
+        .iter(world)
+        .map(|(entity, priority)| (entity, *priority))
+        .collect();
+    priorities.sort_by_key(|(_, priority)| priority.rank);
+
+    let values: Vec<i32> = priorities.iter().map(|(_, p)| p.value).collect();
+    assert_eq!(
+        values,
+        vec![1, 1, 3, 4, 5],
+        "Expected ranks to follow ascending values, found {:?}",
+        values
+    );
+    assert!(
+        priorities[0].0.index() < priorities[1].0.index(),
+        "Expected equal values to keep their original order, found {:?}",
+        priorities
+    );
+}
+
+#[test]
+fn test_joined_queries() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+
+    unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let world = app.world_mut();
+    let mut query = world.query::<&Health>();
+    let mut values: Vec<i32> = query.iter(world).map(|h| h.value).collect();
+    values.sort();
+
+    assert_eq!(
+        values,
+        vec![10, 11, 12, 13],
+        "Expected only entities matched by both queries to be joined, found {:?}",
+        values
+    );
+}
+
+#[test]
+fn test_param_set() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+
+    unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let world = app.world_mut();
+    let mut query = world.query::<&Armor>();
+    let mut values: Vec<i32> = query.iter(world).map(|a| a.value).collect();
+    values.sort();
+
+    assert_eq!(
+        values,
+        vec![20, 30, 40, 101],
+        "Expected each param set member to update its own matches, found {:?}",
+        values
+    );
+}
+
+#[test]
+fn test_derived_query_data() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+
+    unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let world = app.world_mut();
+    let mut query = world.query::<&Stamina>();
+
+    assert_eq!(query.iter(world).count(), 3);
+    for stamina in query.iter(world) {
+        assert_eq!(
+            stamina.current, stamina.max,
+            "Expected stamina to be restored, found {:?}",
+            stamina
+        );
+    }
+}
+
+#[test]
+fn test_derived_bundle() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+
+    unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let world = app.world_mut();
+    let mut query = world.query::<(&Level, &Experience)>();
+    let heroes: Vec<(u32, u32)> = query
+        .iter(world)
+        .map(|(level, experience)| (level.value, experience.points))
+        .collect();
+
+    assert_eq!(
+        heroes,
+        vec![(7, 1200)],
+        "Expected the nested bundle to spawn both components, found {:?}",
+        heroes
+    );
+}
+
+#[test]
+fn test_required_components() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+
+    let library =
+        unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let world = app.world_mut();
+    let mut query = world.query::<(&Hunger, &Decay)>();
+    let mut values: Vec<i32> = query.iter(world).map(|(hunger, _)| hunger.value).collect();
+    values.sort();
+
+    assert_eq!(
+        values,
+        vec![5, 50, 50],
+        "Expected required components to be inserted on spawn, found {:?}",
+        values
+    );
+
+    // `Ghoul` requires `Zombie`, which requires `Hunger` and `Decay`
+    let ghoul_id = world
+        .resource::<SharedRegistry>()
+        .get_component_id("bevy_mod_ffi_test_guest::Ghoul")
+        .unwrap();
+    let ghoul = world.spawn_empty().id();
+    OwningPtr::make((), |ptr| unsafe {
+        world.entity_mut(ghoul).insert_by_id(ghoul_id, ptr);
+    });
+    assert_eq!(
+        world.get::<Hunger>(ghoul).map(|hunger| hunger.value),
+        Some(50),
+        "Expected required components to be inserted by the host"
+    );
+    assert!(world.get::<Decay>(ghoul).is_some());
+
+    library.unload(world);
+    let ghoul = world.spawn_empty().id();
+    OwningPtr::make((), |ptr| unsafe {
+        world.entity_mut(ghoul).insert_by_id(ghoul_id, ptr);
+    });
+    assert!(
+        world.get::<Hunger>(ghoul).is_none(),
+        "Expected unloading to remove the guest's required components"
+    );
+}
+
+#[test]
+fn test_component_clone_behavior() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+
+    let _library =
+        unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let world = app.world_mut();
+    let registry = world.resource::<SharedRegistry>();
+    let leash_id = registry
+        .get_component_id("bevy_mod_ffi_test_guest::Leash")
+        .unwrap();
+    let charge_id = registry
+        .get_component_id("bevy_mod_ffi_test_guest::Charge")
+        .unwrap();
+    let soul_id = registry
+        .get_component_id("bevy_mod_ffi_test_guest::Soul")
+        .unwrap();
+
+    let source = world
+        .query::<EntityRef>()
+        .iter(world)
+        .find(|entity| entity.contains_id(leash_id))
+        .unwrap()
+        .id();
+    let clone = world.entity_mut(source).clone_and_spawn();
+
+    let leash_owner = |entity: Entity| unsafe {
+        *world
+            .entity(entity)
+            .get_by_id(leash_id)
+            .unwrap()
+            .as_ptr()
+            .cast::<u64>()
+    };
+    let charge = |entity: Entity| unsafe {
+        *world
+            .entity(entity)
+            .get_by_id(charge_id)
+            .unwrap()
+            .as_ptr()
+            .cast::<i32>()
+    };
+    assert_eq!(
+        leash_owner(clone),
+        clone.to_bits(),
+        "Expected the cloned leash to point at the clone"
+    );
+    assert_eq!(
+        charge(clone),
+        40,
+        "Expected the custom clone behavior to split the charge"
+    );
+    assert_eq!(charge(source), 80);
+    assert!(
+        !world.entity(clone).contains_id(soul_id),
+        "Expected ignored components to be skipped"
+    );
+}
+
+#[test]
+fn test_immutable_components() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+
+    unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let world = app.world_mut();
+    let badge_id = world
+        .resource::<SharedRegistry>()
+        .get_component_id("bevy_mod_ffi_test_guest::Badge")
+        .unwrap();
+    assert!(
+        !world.components().get_info(badge_id).unwrap().mutable(),
+        "Expected the badge to be registered as immutable"
+    );
+
+    let entity = world
+        .query::<EntityRef>()
+        .iter(world)
+        .find(|entity| entity.contains_id(badge_id))
+        .unwrap()
+        .id();
+    assert!(
+        world.entity_mut(entity).get_mut_by_id(badge_id).is_err(),
+        "Expected mutable access to an immutable component to fail"
+    );
+}
+
+#[test]
+fn test_non_pod_components() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+
+    let library =
+        unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let guest = unsafe { libloading::Library::new(&path).unwrap() };
+    let spawn_route: libloading::Symbol<unsafe extern "C" fn(*mut bevy_mod_ffi_core::world)> =
+        unsafe { guest.get(b"spawn_route").unwrap() };
+    let dropped_waypoints: libloading::Symbol<unsafe extern "C" fn() -> usize> =
+        unsafe { guest.get(b"dropped_waypoints").unwrap() };
+
+    unsafe { spawn_route(app.world_mut() as *mut World as *mut bevy_mod_ffi_core::world) };
+
+    let world = app.world_mut();
+    let route_id = world
+        .resource::<SharedRegistry>()
+        .get_component_id("bevy_mod_ffi_test_guest::Route")
+        .unwrap();
+    let source = world
+        .query::<EntityRef>()
+        .iter(world)
+        .find(|entity| entity.contains_id(route_id))
+        .unwrap()
+        .id();
+
+    let clone = world.entity_mut(source).clone_and_spawn();
+    assert!(
+        world.entity(clone).contains_id(route_id),
+        "Expected the route to be cloned with its clone function"
+    );
+    assert_eq!(unsafe { dropped_waypoints() }, 0);
+
+    world.despawn(source);
+    assert_eq!(
+        unsafe { dropped_waypoints() },
+        4,
+        "Expected despawning to drop the route in the guest"
+    );
+
+    library.unload(world);
+    drop(guest);
+    world.despawn(clone);
+
+    let guest = unsafe { libloading::Library::new(&path).unwrap() };
+    let dropped_waypoints: libloading::Symbol<unsafe extern "C" fn() -> usize> =
+        unsafe { guest.get(b"dropped_waypoints").unwrap() };
+    assert_eq!(
+        unsafe { dropped_waypoints() },
+        8,
+        "Expected the route to be dropped by the guest after it was unloaded"
+    );
+}
+
+#[test]
+fn test_host_component_hooks() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+
+    let library =
+        unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let guest = unsafe { libloading::Library::new(&path).unwrap() };
+    let decay_added: libloading::Symbol<unsafe extern "C" fn() -> usize> =
+        unsafe { guest.get(b"decay_added").unwrap() };
+    let added = unsafe { decay_added() };
+
+    let world = app.world_mut();
+    world.spawn(Decay::default());
+    assert_eq!(
+        unsafe { decay_added() },
+        added + 1,
+        "Expected the guest hook to run for host spawns"
+    );
+
+    library.unload(world);
+    world.spawn(Decay::default());
+    assert_eq!(
+        unsafe { decay_added() },
+        added + 1,
+        "Expected the guest hook to be removed on unload"
+    );
+}
+
+#[test]
+fn test_guest_closures_dropped() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+
+    let library =
+        unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let guest = unsafe { libloading::Library::new(&path).unwrap() };
+    let dropped_guards: libloading::Symbol<unsafe extern "C" fn() -> usize> =
+        unsafe { guest.get(b"dropped_guards").unwrap() };
+    let dropped = unsafe { dropped_guards() };
+
+    library.unload(app.world_mut());
+    assert_eq!(
+        unsafe { dropped_guards() },
+        dropped + 2,
+        "Expected unloading to drop the closures of the guest observers"
+    );
+}
+
+#[test]
+fn test_deferred_world_access() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+
+    let _library =
+        unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let world = app.world_mut();
+    let tally = world.resource::<Tally>();
+    assert_eq!(
+        tally.wounds, 1,
+        "Expected the wound hook to update a resource"
+    );
+    assert_eq!(
+        tally.alarms, 30,
+        "Expected the alarm triggered from the hook to be observed"
+    );
+
+    let mut query = world.query::<&Experience>();
+    assert!(
+        query.iter(world).any(|experience| experience.points == 70),
+        "Expected the wound hook to mutate another component"
+    );
+}
+
+#[test]
+fn test_resource_scope_keeps_ticks() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+    let added = app
+        .world()
+        .get_resource_change_ticks::<Tally>()
+        .unwrap()
+        .added;
+
+    let _library =
+        unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let world = app.world();
+    assert_eq!(world.resource::<Tally>().scopes, 2);
+    assert_eq!(
+        world.get_resource_change_ticks::<Tally>().unwrap().added,
+        added,
+        "Expected the resource scope to keep the added tick of the resource"
+    );
+}
+
+#[test]
+fn test_world_access() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+
+    unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let world = app.world_mut();
+    assert_eq!(
+        world.resource::<Tally>().scopes,
+        2,
+        "Expected the guest to mutate the resource inside and outside a scope"
+    );
+
+    let mut query = world.query::<&Level>();
+    assert!(
+        query.iter(world).all(|level| level.value != 4),
+        "Expected the guest to despawn the entity it leveled up"
+    );
+}
+
+#[test]
+fn test_hierarchy() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+
+    unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let world = app.world_mut();
+    let mut query = world.query::<(&Level, &Children)>();
+    let (_, children) = query
+        .iter(world)
+        .find(|(level, _)| level.value == 10)
+        .expect("Expected the guest to spawn a parent");
+    let children = children.to_vec();
+
+    let mut levels: Vec<_> = children
+        .iter()
+        .map(|child| world.get::<Level>(*child).unwrap().value)
+        .collect();
+    levels.sort();
+    assert_eq!(
+        levels,
+        [11, 12, 13, 14],
+        "Expected the guest to link each child to its parent"
+    );
+
+    let mut query = world.query::<&Level>();
+    assert!(
+        query.iter(world).all(|level| level.value != 21),
+        "Expected the guest to despawn the relationship target"
+    );
+}
+
+#[test]
+fn test_spawn_batch() {
+    let mut app = setup_app();
+    let path = get_guest_library_path();
+
+    unsafe { bevy_mod_ffi::run(&path, app.world_mut()).expect("Failed to load guest library") };
+
+    let world = app.world_mut();
+    let mut query = world.query::<&Level>();
+    let mut levels: Vec<u32> = query
+        .iter(world)
+        .map(|level| level.value)
+        .filter(|value| *value >= 1000)
+        .collect();
+    levels.sort();
+    assert_eq!(
+        levels,
+        (1000..2000).collect::<Vec<_>>(),
+        "Expected the guest to spawn a batch of entities"
+    );
+}
+
+static DROPPED_VALUES: AtomicUsize = AtomicUsize::new(0);
+static LAST_DROPPED: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+unsafe extern "C" fn drop_value<const N: usize>(_ptr: *mut u8) {
+    DROPPED_VALUES.fetch_add(1, Ordering::Relaxed);
+    LAST_DROPPED.store(N, Ordering::Relaxed);
+}
+
+macro_rules! drop_fns {
+    ($($n:literal)*) => {
+        [$(drop_value::<$n> as bevy_mod_ffi_core::ComponentDropFn),*]
+    };
+}
+
+#[test]
+fn test_component_drops_released() {
+    // More drop functions than the host has slots for, like a guest that is rebuilt and reloaded
+    let drop_fns = drop_fns!(
+        0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19
+        20 21 22 23 24 25 26 27 28 29 30 31 32 33 34 35 36 37 38 39
+        40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59
+        60 61 62 63 64 65 66 67 68 69 70 71 72 73 74 75 76 77 78 79
+    );
+
+    let mut world = World::new();
+    world.insert_resource(SharedRegistry::default());
+    let handle = Arc::new(());
+    world.insert_resource(CurrentLibraryHandle(Some(LibraryHandle(handle.clone()))));
+
+    for (n, drop_fn) in drop_fns.into_iter().enumerate() {
+        let library_id = world.resource_mut::<SharedRegistry>().new_library_id();
+        world
+            .resource_mut::<SharedRegistry>()
+            .set_current_library(Some(library_id));
+
+        let name = format!("Dropped{n}");
+        let name_cstring = CString::new(name.clone()).unwrap();
+        let name_bytes = name_cstring.as_bytes_with_nul();
+        let mut id = 0;
+        let result = unsafe {
+            bevy_world_register_component(
+                &mut world as *mut World as *mut bevy_mod_ffi_core::world,
+                name_bytes.as_ptr(),
+                name_bytes.len(),
+                size_of::<u64>(),
+                align_of::<u64>(),
+                1,
+                true,
+                Some(drop_fn),
+                None,
+                None,
+                None,
+                None,
+                None,
+                true,
+                None,
+                ptr::null(),
+                None,
+                &mut id,
+            )
+        };
+        assert_eq!(
+            result,
+            RegisterComponentResult::Registered,
+            "Expected component {n} to be registered"
+        );
+
+        let entity = world.spawn_empty().id();
+        OwningPtr::make(n as u64, |ptr| unsafe {
+            world
+                .entity_mut(entity)
+                .insert_by_id(ComponentId::new(id), ptr);
+        });
+
+        world.resource_scope(|world, mut registry: Mut<SharedRegistry>| {
+            registry.remove_library_component_drops(library_id, world);
+        });
+        assert!(
+            world
+                .resource::<SharedRegistry>()
+                .get_component_id(&name)
+                .is_some(),
+            "Expected the drop function of component {n} to be kept while it has values"
+        );
+
+        world.despawn(entity);
+        assert_eq!(
+            LAST_DROPPED.load(Ordering::Relaxed),
+            n,
+            "Expected component {n} to be dropped by its own drop function"
+        );
+
+        world.resource_scope(|world, mut registry: Mut<SharedRegistry>| {
+            registry.release_component_drops(world);
+        });
+        assert!(
+            world
+                .resource::<SharedRegistry>()
+                .get_component_id(&name)
+                .is_none(),
+            "Expected the drop function of component {n} to be released"
+        );
+    }
+
+    assert_eq!(DROPPED_VALUES.load(Ordering::Relaxed), drop_fns.len());
+    world.remove_resource::<CurrentLibraryHandle>();
+    assert_eq!(
+        Arc::strong_count(&handle),
+        1,
+        "Expected released drop functions to release their library"
+    );
+}
+
+unsafe extern "C" fn count_runs(
+    f_ptr: *mut (),
+    params: *const *mut dyn_system_param,
+    params_len: usize,
+    _input_ptr: *const u8,
+    _output_ptr: *mut u8,
+) {
+    unsafe {
+        *(f_ptr as *mut usize) += 1;
+        for param in slice::from_raw_parts(params, params_len) {
+            bevy_dyn_system_params_drop(*param);
+        }
+    }
+}
+
+unsafe extern "C" fn drop_runs(f_ptr: *mut ()) {
+    drop(unsafe { Box::from_raw(f_ptr as *mut usize) });
+}
+
+#[test]
+fn test_skipped_system_reported() {
+    let mut world = World::new();
+    let counter_id = world.register_component::<Counter>();
+    let world_ptr = &mut world as *mut World as *mut bevy_mod_ffi_core::world;
+
+    let runs = Box::into_raw(Box::new(0usize));
+    let system = unsafe {
+        let query_builder = bevy_query_builder_new(world_ptr);
+        bevy_query_builder_with(query_builder, counter_id.index());
+
+        let mut builder = ptr::null_mut();
+        assert!(bevy_param_builder_new(&mut builder));
+        assert!(bevy_param_builder_add_populated(builder, query_builder));
+        let mut state = ptr::null_mut();
+        assert!(bevy_param_builder_build(world_ptr, builder, &mut state));
+
+        let mut system = ptr::null_mut();
+        bevy_system_state_build(state, runs as *mut (), count_runs, drop_runs, &mut system);
+        system
+    };
+
+    let ran = unsafe { bevy_world_run_system(world_ptr, system, ptr::null_mut(), ptr::null_mut()) };
+    assert!(
+        !ran,
+        "Expected a system with an empty populated query to be skipped"
+    );
+    assert_eq!(unsafe { *runs }, 0);
+
+    world.spawn(Counter { value: 1 });
+    let world_ptr = &mut world as *mut World as *mut bevy_mod_ffi_core::world;
+    let ran = unsafe { bevy_world_run_system(world_ptr, system, ptr::null_mut(), ptr::null_mut()) };
+    assert!(ran, "Expected a system with a populated query to run");
+    assert_eq!(unsafe { *runs }, 1);
+
+    unsafe { bevy_system_drop(system) };
+}